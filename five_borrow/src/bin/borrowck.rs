@@ -0,0 +1,58 @@
+// 玩具借用检查器: cargo run --bin borrowck [源文件]
+// 不带参数时验证全部课程场景，带参数时检查并运行指定的源文件
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use five_borrow::borrowck::{self, scenarios::SCENARIOS};
+
+fn main() -> ExitCode {
+    match env::args().nth(1) {
+        Some(path) => run_file(&path),
+        None => verify_scenarios(),
+    }
+}
+
+fn run_file(path: &str) -> ExitCode {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("读取 {path} 失败: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match borrowck::run(&source) {
+        Ok(output) => {
+            for line in output {
+                println!("{line}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{error}\n");
+            }
+            eprintln!("检查失败: {} 个错误", errors.len());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn verify_scenarios() -> ExitCode {
+    let mut failed = 0;
+    for scenario in SCENARIOS {
+        match scenario.verify() {
+            Ok(()) => println!("✓ {} ({})", scenario.name, scenario.origin),
+            Err(reason) => {
+                failed += 1;
+                println!("✗ {} ({})\n{reason}", scenario.name, scenario.origin);
+            }
+        }
+        // 报错的场景顺便展示诊断信息
+        if let Err(errors) = borrowck::check(scenario.source) {
+            println!("{}\n", errors[0]);
+        }
+    }
+    println!("{} 个场景，{failed} 个不符合预期", SCENARIOS.len());
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
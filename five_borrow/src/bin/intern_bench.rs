@@ -0,0 +1,90 @@
+// Interner 与 String 的对比: cargo run --release --bin intern_bench
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use five_borrow::interner::{Interner, SharedInterner, StaticInterner, Symbol};
+
+const TOKENS: usize = 2_000_000;
+const DISTINCT: usize = 5_000;
+const THREADS: usize = 4;
+
+// 模拟分词器输出: 少量不同的标识符大量重复
+fn identifiers() -> Vec<String> {
+    let words: Vec<String> = (0..DISTINCT).map(|i| format!("identifier_{i}")).collect();
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..TOKENS)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            words[state as usize % DISTINCT].clone()
+        })
+        .collect()
+}
+
+fn time<T>(name: &str, f: impl FnOnce() -> T) -> Duration {
+    let start = Instant::now();
+    black_box(f());
+    let elapsed = start.elapsed();
+    println!("{name:<28} {:>8.1} ms", elapsed.as_secs_f64() * 1000.0);
+    elapsed
+}
+
+fn main() {
+    let input = identifiers();
+    println!("{TOKENS} 个标识符，{DISTINCT} 个不同值\n");
+
+    time("String::clone", || input.iter().map(|s| s.to_string()).collect::<Vec<String>>());
+    time("Interner::intern", || {
+        let mut interner = Interner::new();
+        input.iter().map(|s| interner.intern(s)).collect::<Vec<Symbol>>()
+    });
+    time("StaticInterner::intern", || {
+        let interner = StaticInterner::new();
+        input.iter().map(|s| interner.intern(s)).collect::<Vec<Symbol>>()
+    });
+    // 各线程的输入提前拷贝好，计时只包含 intern 本身
+    let chunks: Vec<Vec<String>> = input.chunks(TOKENS / THREADS).map(|c| c.to_vec()).collect();
+    time(&format!("SharedInterner x{THREADS} 线程"), move || {
+        let interner = Arc::new(SharedInterner::new());
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                let interner = Arc::clone(&interner);
+                thread::spawn(move || chunk.iter().map(|s| interner.intern(s)).collect::<Vec<Symbol>>())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().expect("线程 panic").len()).sum::<usize>()
+    });
+
+    // 计数: 以 String 为键与以 Symbol 为键
+    let mut interner = Interner::new();
+    let symbols: Vec<Symbol> = input.iter().map(|s| interner.intern(s)).collect();
+    time("HashMap<String, usize> 计数", || {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        // 命中时只查找，不复制字符串；未命中才分配新的键
+        for s in &input {
+            match counts.get_mut(s.as_str()) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(s.to_owned(), 1);
+                }
+            }
+        }
+        counts.len()
+    });
+    time("HashMap<Symbol, usize> 计数", || {
+        let mut counts: HashMap<Symbol, usize> = HashMap::new();
+        for &sym in &symbols {
+            *counts.entry(sym).or_insert(0) += 1;
+        }
+        counts.len()
+    });
+
+    let string_bytes = input.iter().map(|s| s.capacity() + size_of::<String>()).sum::<usize>();
+    let symbol_bytes = symbols.len() * size_of::<Symbol>() + interner.iter().map(|(_, s)| s.len()).sum::<usize>();
+    println!("\n内存: String {} KB，Symbol + 字符串表 {} KB", string_bytes / 1024, symbol_bytes / 1024);
+}
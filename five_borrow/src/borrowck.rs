@@ -0,0 +1,141 @@
+// ---------------- 模块: 玩具借用检查器 ----------------
+/*
+    what: 课程里很多借用错误只能写成注释，这里用一门迷你语言把它们变成可以运行、检查的例子。
+
+    borrowck            borrowck.rs            入口: check / run，诊断信息
+    ├── lexer           borrowck/lexer.rs      词法分析
+    ├── parser          borrowck/parser.rs     语法树
+    ├── checker         borrowck/checker.rs    借用检查
+    ├── eval            borrowck/eval.rs       检查通过后解释执行
+    └── scenarios       borrowck/scenarios.rs  课程中的例子及预期结果
+
+    语言:
+        let s = "Jamie";            字符串: 非 Copy，按值使用时移动
+        let mut n = 5;              整数: Copy
+        let r = &s;  let m = &mut n;  *m = 6;
+        let r;  { let x = 1; r = &x; }   先声明后赋值、块作用域
+        s.len()  s.clone()  s.push_str("!")  方法: push_str / push / clear 需要可变
+        print(s, *r)                print 只借用参数
+        drop(s)  takes(s)           其他函数按值接收参数，返回第一个参数（所有权原样交回）
+
+    检查:
+        - 作用域: 块结束时其中的变量被释放，之后仍在使用的引用报 "活得不够长"（悬垂引用）;
+        - 活跃性（类似 NLL）: 借用从创建持续到持有它的变量最后一次使用，
+          同一语句中的使用都算到语句结束;
+        - 错误码沿用 rustc: E0382 使用已移动的值、E0499 两个可变借用、E0502 可变与不可变借用冲突、
+          E0597 悬垂引用 等，带行列位置;
+        - 类型: 赋值前后的类型必须一致（E0308），引用不会指向自己;
+*/
+
+use std::fmt;
+
+mod checker;
+mod eval;
+mod lexer;
+mod parser;
+pub mod scenarios;
+
+// 行、列都从 1 开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Syntax,
+    UnknownVariable,     // E0425
+    Uninitialized,       // E0381
+    UseAfterMove,        // E0382
+    AssignTwice,         // E0384
+    UseWhileMutBorrowed, // E0503
+    SecondMutBorrow,     // E0499
+    BorrowConflict,      // E0502
+    MoveWhileBorrowed,   // E0505
+    AssignToBorrowed,    // E0506
+    MoveOutOfBorrow,     // E0507
+    NotMutable,          // E0596
+    WriteThroughShared,  // E0594
+    Dangling,            // E0597
+    NotAReference,       // E0614
+    Mismatch,            // E0308
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Syntax => "syntax",
+            ErrorCode::UnknownVariable => "E0425",
+            ErrorCode::Uninitialized => "E0381",
+            ErrorCode::UseAfterMove => "E0382",
+            ErrorCode::AssignTwice => "E0384",
+            ErrorCode::UseWhileMutBorrowed => "E0503",
+            ErrorCode::SecondMutBorrow => "E0499",
+            ErrorCode::BorrowConflict => "E0502",
+            ErrorCode::MoveWhileBorrowed => "E0505",
+            ErrorCode::AssignToBorrowed => "E0506",
+            ErrorCode::MoveOutOfBorrow => "E0507",
+            ErrorCode::NotMutable => "E0596",
+            ErrorCode::WriteThroughShared => "E0594",
+            ErrorCode::Dangling => "E0597",
+            ErrorCode::NotAReference => "E0614",
+            ErrorCode::Mismatch => "E0308",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: ErrorCode,
+    pub span: Span,
+    pub message: String,
+    // 相关位置，如 "在这里移动"、"在这里借用"
+    pub notes: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    fn new(code: ErrorCode, span: Span, message: impl Into<String>) -> Self {
+        Diagnostic { code, span, message: message.into(), notes: Vec::new() }
+    }
+
+    fn note(mut self, span: Span, text: impl Into<String>) -> Self {
+        self.notes.push((span, text.into()));
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error[{}]: {}\n  --> {}", self.code.as_str(), self.message, self.span)?;
+        for (span, text) in &self.notes {
+            write!(f, "\n   = {span}: {text}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+// 只检查，返回全部错误
+pub fn check(source: &str) -> Result<(), Vec<Diagnostic>> {
+    let program = parser::parse(source).map_err(|e| vec![e])?;
+    let errors = checker::check(&program);
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+// 检查通过后执行，返回 print 的输出
+pub fn run(source: &str) -> Result<Vec<String>, Vec<Diagnostic>> {
+    let program = parser::parse(source).map_err(|e| vec![e])?;
+    let errors = checker::check(&program);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(eval::run(&program))
+}
@@ -0,0 +1,759 @@
+// 借用检查: 两遍遍历
+/*
+    1. Facts::collect: 解析变量名到绑定（处理作用域和遮蔽），记录每个绑定在哪些语句被使用、被赋值;
+    2. Checker: 按执行顺序模拟每个绑定的抽象值（Int 可 Copy、Str 会移动、引用带着它持有的借用），
+       在每次访问时检查冲突。
+
+    语句按遍历顺序编号，块的右花括号单独占一个编号。借用在第 p 条语句是否活跃:
+        - 在第 p 条语句中创建的借用（包括 print(&s) 这类临时借用）在本语句内活跃;
+        - 否则看持有它的变量: 在 p 及之后、被重新赋值之前还会被使用，借用就活跃（类似 NLL）。
+
+    借用的对象是位置 Place: 变量 s，或引用 r 指向的 *r。再借用 &*r、&mut *r 记一个新的 *r 借用，
+    同时沿用 r 的借用; 之后经过 r 的读写、再借用、移动 r 都要和它比较。
+    r 被重新赋值后指向别处，旧的 *r 借用不再和 r 冲突。
+*/
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::parser::{Expr, ExprKind, Program, Stmt};
+use super::{Diagnostic, ErrorCode, Span};
+
+type BindingId = usize;
+type LoanId = usize;
+
+// 需要 &mut self 的方法，其他方法只读
+const MUTATING_METHODS: &[&str] = &["push_str", "push", "clear"];
+
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let mut facts = Facts::collect(program);
+    let mut checker = Checker {
+        facts: &facts,
+        pos: 0,
+        bindings: (0..facts.uses.len()).map(|_| None).collect(),
+        scopes: vec![Vec::new()],
+        loans: Vec::new(),
+        errors: Vec::new(),
+    };
+    checker.stmts(&program.stmts);
+    let mut errors = checker.errors;
+    errors.append(&mut facts.errors);
+    errors.sort_by_key(|e| e.span);
+    errors
+}
+
+// ---------- 第一遍: 名字解析和使用位置 ----------
+
+#[derive(Default)]
+struct Facts {
+    // 变量名出现的位置（包括 let 声明处）→ 绑定
+    resolved: HashMap<Span, BindingId>,
+    // 每个绑定被使用的 (语句编号, 位置)，按顺序
+    uses: Vec<Vec<(usize, Span)>>,
+    // 每个绑定被赋值的语句编号
+    defs: Vec<Vec<usize>>,
+    errors: Vec<Diagnostic>,
+    pos: usize,
+    scopes: Vec<Vec<(String, BindingId)>>,
+}
+
+impl Facts {
+    fn collect(program: &Program) -> Facts {
+        let mut facts = Facts { scopes: vec![Vec::new()], ..Facts::default() };
+        facts.stmts(&program.stmts);
+        facts
+    }
+
+    fn lookup(&mut self, name: &str, span: Span) -> Option<BindingId> {
+        let found = self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|(n, _)| n == name);
+        match found {
+            Some(&(_, id)) => {
+                self.resolved.insert(span, id);
+                Some(id)
+            }
+            None => {
+                self.errors.push(Diagnostic::new(ErrorCode::UnknownVariable, span, format!("找不到变量 `{name}`")));
+                None
+            }
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.pos += 1;
+            let pos = self.pos;
+            match stmt {
+                Stmt::Let { name, span, init, .. } => {
+                    if let Some(init) = init {
+                        self.expr(init, pos);
+                    }
+                    let id = self.uses.len();
+                    self.uses.push(Vec::new());
+                    self.defs.push(vec![pos]);
+                    self.resolved.insert(*span, id);
+                    self.scopes.last_mut().expect("至少有一层作用域").push((name.clone(), id));
+                }
+                Stmt::Assign { name, span, deref, value } => {
+                    self.expr(value, pos);
+                    if let Some(id) = self.lookup(name, *span) {
+                        if *deref {
+                            self.uses[id].push((pos, *span));
+                        } else {
+                            self.defs[id].push(pos);
+                        }
+                    }
+                }
+                Stmt::Expr(expr) => self.expr(expr, pos),
+                Stmt::Block { stmts, .. } => {
+                    self.scopes.push(Vec::new());
+                    self.stmts(stmts);
+                    self.pos += 1;
+                    self.scopes.pop();
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, pos: usize) {
+        match &expr.kind {
+            ExprKind::Int(_) | ExprKind::Str(_) => {}
+            ExprKind::Var(name) => {
+                if let Some(id) = self.lookup(name, expr.span) {
+                    self.uses[id].push((pos, expr.span));
+                }
+            }
+            ExprKind::Borrow { place: inner, .. } | ExprKind::Deref(inner) => self.expr(inner, pos),
+            ExprKind::Call { args, .. } => args.iter().for_each(|arg| self.expr(arg, pos)),
+            ExprKind::Method { recv, args, .. } => {
+                self.expr(recv, pos);
+                args.iter().for_each(|arg| self.expr(arg, pos));
+            }
+        }
+    }
+}
+
+// ---------- 第二遍: 模拟执行 ----------
+
+#[derive(Debug, Clone)]
+enum Value {
+    Unit,
+    Int,
+    Str,
+    Ref { mutable: bool, target: BindingId, loans: Vec<LoanId> },
+}
+
+impl Value {
+    // 整数和 & 引用按位复制，String 和 &mut 引用会移动
+    fn is_copy(&self) -> bool {
+        !matches!(self, Value::Str | Value::Ref { mutable: true, .. })
+    }
+}
+
+// 值的类型，只用来检查赋值前后一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ty {
+    Unit,
+    Int,
+    Str,
+    Ref { mutable: bool, inner: Box<Ty> },
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ty::Unit => write!(f, "()"),
+            Ty::Int => write!(f, "i64"),
+            Ty::Str => write!(f, "String"),
+            Ty::Ref { mutable: true, inner } => write!(f, "&mut {inner}"),
+            Ty::Ref { mutable: false, inner } => write!(f, "&{inner}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Uninit,
+    Init,
+    Moved(Span),
+}
+
+struct Binding {
+    name: String,
+    mutable: bool,
+    state: State,
+    value: Value,
+}
+
+// 被借用的位置，base 相同的两个位置互相重叠
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Place {
+    Var(BindingId),
+    // 引用变量指向的值
+    Deref(BindingId),
+}
+
+impl Place {
+    fn base(self) -> BindingId {
+        match self {
+            Place::Var(id) | Place::Deref(id) => id,
+        }
+    }
+}
+
+struct Loan {
+    place: Place,
+    mutable: bool,
+    span: Span,
+    // 创建时的语句编号
+    created: usize,
+    // 持有这个借用的变量
+    holders: Vec<BindingId>,
+    // 被借用的变量已离开作用域; *r 借用则是 r 已离开作用域或指向了别处
+    released: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    // 按值使用: let、赋值、函数参数
+    Move,
+    // 只借用: print 的参数
+    Borrow,
+}
+
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Borrow { mutable: bool },
+    Move,
+    Write,
+}
+
+struct Checker<'a> {
+    facts: &'a Facts,
+    pos: usize,
+    // 离开作用域后也保留，引用的目标总能查到
+    bindings: Vec<Option<Binding>>,
+    scopes: Vec<Vec<BindingId>>,
+    loans: Vec<Loan>,
+    errors: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn binding(&self, id: BindingId) -> &Binding {
+        self.bindings[id].as_ref().expect("绑定在使用前已声明")
+    }
+
+    fn binding_mut(&mut self, id: BindingId) -> &mut Binding {
+        self.bindings[id].as_mut().expect("绑定在使用前已声明")
+    }
+
+    // 第一遍已经报过找不到变量的错误
+    fn resolve(&self, span: Span) -> Option<BindingId> {
+        self.facts.resolved.get(&span).copied()
+    }
+
+    fn error(&mut self, diagnostic: Diagnostic) {
+        self.errors.push(diagnostic);
+    }
+
+    // ---------- 活跃性 ----------
+
+    // 持有者在当前语句及之后、被重新赋值之前的第一次使用
+    fn next_use(&self, holder: BindingId) -> Option<Span> {
+        let pos = self.pos;
+        let kill = self.facts.defs[holder].iter().copied().filter(|&d| d >= pos).min();
+        self.facts.uses[holder]
+            .iter()
+            .find(|&&(u, _)| u >= pos && kill.is_none_or(|d| u <= d))
+            .map(|&(_, span)| span)
+    }
+
+    // 借用仍然活跃时，返回让它活跃的那次使用
+    fn live_use(&self, loan: &Loan) -> Option<Span> {
+        if loan.released {
+            return None;
+        }
+        if loan.created == self.pos {
+            return Some(loan.span);
+        }
+        loan.holders.iter().filter_map(|&h| self.next_use(h)).min()
+    }
+
+    fn hold(&mut self, holder: BindingId, value: &Value) {
+        if let Value::Ref { loans, .. } = value {
+            for &loan in loans {
+                self.loans[loan].holders.push(holder);
+            }
+        }
+    }
+
+    fn release_holder(&mut self, holder: BindingId) {
+        for loan in &mut self.loans {
+            loan.holders.retain(|&h| h != holder);
+        }
+    }
+
+    fn ty(&self, value: &Value) -> Ty {
+        self.ty_within(value, self.bindings.len())
+    }
+
+    // depth 防止引用成环时无限递归（类型检查本身保证不会成环）
+    fn ty_within(&self, value: &Value, depth: usize) -> Ty {
+        match value {
+            Value::Unit => Ty::Unit,
+            Value::Int => Ty::Int,
+            Value::Str => Ty::Str,
+            Value::Ref { mutable, target, .. } => {
+                let inner = if depth == 0 { Ty::Unit } else { self.ty_within(&self.binding(*target).value, depth - 1) };
+                Ty::Ref { mutable: *mutable, inner: Box::new(inner) }
+            }
+        }
+    }
+
+    // 已初始化的变量只能赋同类型的值，否则 *r = &r 会让引用指向自己
+    fn check_type(&mut self, target: BindingId, value: &Value, span: Span) -> bool {
+        let binding = self.binding(target);
+        if matches!(binding.state, State::Uninit) {
+            return true;
+        }
+        let (expected, found) = (self.ty(&binding.value), self.ty(value));
+        if expected == found {
+            return true;
+        }
+        let message = format!("类型不匹配: `{}` 的类型是 {expected}，不能赋值为 {found}", binding.name);
+        self.error(Diagnostic::new(ErrorCode::Mismatch, span, message));
+        false
+    }
+
+    // ---------- 访问检查 ----------
+
+    fn require_init(&mut self, id: BindingId, span: Span) -> bool {
+        let binding = self.binding(id);
+        let diagnostic = match binding.state {
+            State::Init => return true,
+            State::Uninit => {
+                Diagnostic::new(ErrorCode::Uninitialized, span, format!("使用了未初始化的变量 `{}`", binding.name))
+            }
+            State::Moved(at) => {
+                Diagnostic::new(ErrorCode::UseAfterMove, span, format!("使用了已移动的值 `{}`", binding.name))
+                    .note(at, "值在这里被移动")
+            }
+        };
+        self.error(diagnostic);
+        false
+    }
+
+    fn place_name(&self, place: Place) -> String {
+        match place {
+            Place::Var(id) => self.binding(id).name.clone(),
+            Place::Deref(id) => format!("*{}", self.binding(id).name),
+        }
+    }
+
+    // 访问变量或 *r 时，和重叠位置上仍活跃的借用冲突就报错;
+    // 给 r 本身赋值不影响 *r 上的借用
+    fn access(&mut self, place: Place, access: Access, span: Span) {
+        let overlaps = |loan: &Loan| match (place, access) {
+            (Place::Var(_), Access::Write) => loan.place == place,
+            _ => loan.place.base() == place.base(),
+        };
+        let conflict = self
+            .loans
+            .iter()
+            .filter(|loan| overlaps(loan))
+            .filter(|loan| loan.mutable || matches!(access, Access::Borrow { mutable: true } | Access::Move | Access::Write))
+            .find_map(|loan| self.live_use(loan).map(|later| (loan.place, loan.mutable, loan.span, later)));
+        let Some((loan_place, loan_mutable, loan_span, later)) = conflict else {
+            return;
+        };
+        let name = &self.place_name(place);
+        let (code, message) = match access {
+            Access::Read => (ErrorCode::UseWhileMutBorrowed, format!("`{name}` 已被可变借用，不能使用")),
+            Access::Borrow { mutable: false } => {
+                (ErrorCode::BorrowConflict, format!("`{name}` 已被可变借用，不能再借用为不可变"))
+            }
+            Access::Borrow { mutable: true } if loan_mutable => {
+                (ErrorCode::SecondMutBorrow, format!("`{name}` 不能同时被可变借用两次"))
+            }
+            Access::Borrow { mutable: true } => {
+                (ErrorCode::BorrowConflict, format!("`{name}` 已被不可变借用，不能再借用为可变"))
+            }
+            Access::Move => (ErrorCode::MoveWhileBorrowed, format!("`{name}` 被借用期间不能移动")),
+            Access::Write => (ErrorCode::AssignToBorrowed, format!("`{name}` 被借用期间不能赋值")),
+        };
+        let kind = if loan_mutable { "可变" } else { "不可变" };
+        let loan_name = self.place_name(loan_place);
+        let mut diagnostic =
+            Diagnostic::new(code, span, message).note(loan_span, format!("`{loan_name}` 在这里被{kind}借用"));
+        if later != loan_span {
+            diagnostic = diagnostic.note(later, "借用在这里之后仍被使用");
+        }
+        self.error(diagnostic);
+    }
+
+    // ---------- 语句 ----------
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.pos += 1;
+            match stmt {
+                Stmt::Let { name, span, mutable, init } => {
+                    let value = init.as_ref().map(|init| self.expr(init, Mode::Move));
+                    let id = self.facts.resolved[span];
+                    let state = if value.is_some() { State::Init } else { State::Uninit };
+                    let value = value.unwrap_or(Value::Unit);
+                    self.hold(id, &value);
+                    self.bindings[id] = Some(Binding { name: name.clone(), mutable: *mutable, state, value });
+                    self.scopes.last_mut().expect("至少有一层作用域").push(id);
+                }
+                Stmt::Assign { span, deref: false, value, .. } => {
+                    let value = self.expr(value, Mode::Move);
+                    let Some(id) = self.resolve(*span) else { continue };
+                    let binding = self.binding(id);
+                    // 不可变变量允许先声明、后赋值一次
+                    if !binding.mutable && !matches!(binding.state, State::Uninit) {
+                        let message = format!("不能对不可变变量 `{}` 赋值两次", binding.name);
+                        self.error(Diagnostic::new(ErrorCode::AssignTwice, *span, message));
+                    }
+                    if !self.check_type(id, &value, *span) {
+                        continue;
+                    }
+                    self.access(Place::Var(id), Access::Write, *span);
+                    self.release_reborrows(id);
+                    self.release_holder(id);
+                    self.hold(id, &value);
+                    let binding = self.binding_mut(id);
+                    binding.state = State::Init;
+                    binding.value = value;
+                }
+                Stmt::Assign { span, deref: true, value, .. } => {
+                    let value = self.expr(value, Mode::Move);
+                    let Some(id) = self.resolve(*span) else { continue };
+                    if !self.require_init(id, *span) {
+                        continue;
+                    }
+                    let name = self.binding(id).name.clone();
+                    match self.binding(id).value.clone() {
+                        Value::Ref { mutable: true, target, .. } => {
+                            if self.check_type(target, &value, *span) {
+                                self.access(Place::Deref(id), Access::Write, *span);
+                                self.release_holder(target);
+                                self.hold(target, &value);
+                                self.binding_mut(target).value = value;
+                            }
+                        }
+                        Value::Ref { mutable: false, .. } => self.error(Diagnostic::new(
+                            ErrorCode::WriteThroughShared,
+                            *span,
+                            format!("`*{name}` 在 & 引用之后，不能赋值"),
+                        )),
+                        _ => self.error(not_a_reference(&name, *span)),
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    self.expr(expr, Mode::Move);
+                }
+                Stmt::Block { stmts, close } => {
+                    self.scopes.push(Vec::new());
+                    self.stmts(stmts);
+                    self.pos += 1;
+                    let ended = self.scopes.pop().expect("刚压入的作用域");
+                    for &id in ended.iter().rev() {
+                        self.drop_binding(id, *close);
+                    }
+                }
+            }
+        }
+    }
+
+    // 变量离开作用域: 块外仍会使用的引用就成了悬垂引用;
+    // *r 上的再借用指向的是 r 的目标，r 离开作用域时不会悬垂
+    fn drop_binding(&mut self, id: BindingId, close: Span) {
+        self.release_reborrows(id);
+        for loan in 0..self.loans.len() {
+            if self.loans[loan].place != Place::Var(id) {
+                continue;
+            }
+            if let Some(later) = self.live_use(&self.loans[loan]) {
+                let name = &self.binding(id).name;
+                let diagnostic =
+                    Diagnostic::new(ErrorCode::Dangling, self.loans[loan].span, format!("`{name}` 活得不够长（悬垂引用）"))
+                        .note(close, format!("`{name}` 在这里被释放，但仍被借用"))
+                        .note(later, "借用在这里之后仍被使用");
+                self.error(diagnostic);
+            }
+            self.loans[loan].released = true;
+        }
+    }
+
+    // r 被重新赋值或离开作用域，旧的 *r 借用不再和 r 冲突（它们沿用的目标借用仍然有效）
+    fn release_reborrows(&mut self, id: BindingId) {
+        for loan in &mut self.loans {
+            if loan.place == Place::Deref(id) {
+                loan.released = true;
+            }
+        }
+    }
+
+    // ---------- 表达式 ----------
+
+    fn expr(&mut self, expr: &Expr, mode: Mode) -> Value {
+        match &expr.kind {
+            ExprKind::Int(_) => Value::Int,
+            ExprKind::Str(_) => Value::Str,
+            ExprKind::Var(_) => {
+                let Some(id) = self.resolve(expr.span) else { return Value::Unit };
+                if !self.require_init(id, expr.span) {
+                    return Value::Unit;
+                }
+                let value = self.binding(id).value.clone();
+                match mode {
+                    Mode::Borrow => self.access(Place::Var(id), Access::Borrow { mutable: false }, expr.span),
+                    Mode::Move if value.is_copy() => self.access(Place::Var(id), Access::Read, expr.span),
+                    Mode::Move => {
+                        self.access(Place::Var(id), Access::Move, expr.span);
+                        self.binding_mut(id).state = State::Moved(expr.span);
+                    }
+                }
+                value
+            }
+            ExprKind::Borrow { mutable, place } => self.borrow(place, *mutable, expr.span),
+            ExprKind::Deref(inner) => {
+                let Some((id, _, target, _)) = self.reference(inner) else { return Value::Unit };
+                self.access(Place::Deref(id), Access::Read, inner.span);
+                let value = self.binding(target).value.clone();
+                if mode == Mode::Move && !value.is_copy() {
+                    let name = &self.binding(id).name;
+                    let message = format!("不能从 `*{name}` 移出值，它在引用之后");
+                    self.error(Diagnostic::new(ErrorCode::MoveOutOfBorrow, expr.span, message));
+                }
+                value
+            }
+            ExprKind::Call { func, args } => {
+                // print 只借用参数; 其他函数按值接收，返回第一个参数（drop 除外）
+                let mode = if func == "print" { Mode::Borrow } else { Mode::Move };
+                let values: Vec<Value> = args.iter().map(|arg| self.expr(arg, mode)).collect();
+                match func.as_str() {
+                    "print" | "drop" => Value::Unit,
+                    _ => values.into_iter().next().unwrap_or(Value::Unit),
+                }
+            }
+            ExprKind::Method { recv, method, args } => {
+                let receiver = self.receiver(recv, MUTATING_METHODS.contains(&method.as_str()));
+                for arg in args {
+                    self.expr(arg, Mode::Move);
+                }
+                match method.as_str() {
+                    "clone" => receiver,
+                    "len" => Value::Int,
+                    _ => Value::Unit,
+                }
+            }
+        }
+    }
+
+    // 变量 id 保存的引用: (id, 是否可变, 目标, 借用)
+    fn reference(&mut self, var: &Expr) -> Option<(BindingId, bool, BindingId, Vec<LoanId>)> {
+        let id = self.resolve(var.span)?;
+        if !self.require_init(id, var.span) {
+            return None;
+        }
+        match self.binding(id).value.clone() {
+            Value::Ref { mutable, target, loans } => Some((id, mutable, target, loans)),
+            _ => {
+                let diagnostic = not_a_reference(&self.binding(id).name, var.span);
+                self.error(diagnostic);
+                None
+            }
+        }
+    }
+
+    fn borrow(&mut self, place: &Expr, mutable: bool, span: Span) -> Value {
+        match &place.kind {
+            ExprKind::Var(_) => {
+                let Some(id) = self.resolve(place.span) else { return Value::Unit };
+                if !self.require_init(id, place.span) {
+                    return Value::Unit;
+                }
+                let binding = self.binding(id);
+                if mutable && !binding.mutable {
+                    let message = format!("`{}` 没有声明为 mut，不能借用为可变", binding.name);
+                    self.error(Diagnostic::new(ErrorCode::NotMutable, span, message));
+                }
+                self.access(Place::Var(id), Access::Borrow { mutable }, span);
+                let loan = self.new_loan(Place::Var(id), mutable, span);
+                Value::Ref { mutable, target: id, loans: vec![loan] }
+            }
+            // 再借用: &*r、&mut *r 新增一个 *r 上的借用，并沿用 r 的借用
+            ExprKind::Deref(inner) => {
+                let Some((id, ref_mutable, target, mut loans)) = self.reference(inner) else { return Value::Unit };
+                if mutable && !ref_mutable {
+                    let message = format!("`*{}` 在 & 引用之后，不能借用为可变", self.binding(id).name);
+                    self.error(Diagnostic::new(ErrorCode::NotMutable, span, message));
+                }
+                self.access(Place::Deref(id), Access::Borrow { mutable }, span);
+                loans.push(self.new_loan(Place::Deref(id), mutable, span));
+                Value::Ref { mutable, target, loans }
+            }
+            _ => Value::Unit,
+        }
+    }
+
+    fn new_loan(&mut self, place: Place, mutable: bool, span: Span) -> LoanId {
+        self.loans.push(Loan { place, mutable, span, created: self.pos, holders: Vec::new(), released: false });
+        self.loans.len() - 1
+    }
+
+    // 方法接收者: 引用自动解引用，变量自动借用
+    fn receiver(&mut self, recv: &Expr, mutating: bool) -> Value {
+        let var = match &recv.kind {
+            ExprKind::Var(_) => recv,
+            ExprKind::Deref(inner) => inner,
+            _ => return self.expr(recv, Mode::Move),
+        };
+        let Some(id) = self.resolve(var.span) else { return Value::Unit };
+        if !self.require_init(id, var.span) {
+            return Value::Unit;
+        }
+        let binding = self.binding(id);
+        match binding.value.clone() {
+            Value::Ref { mutable, target, .. } => {
+                if mutating && !mutable {
+                    let message = format!("`*{}` 在 & 引用之后，不能借用为可变", binding.name);
+                    self.error(Diagnostic::new(ErrorCode::NotMutable, recv.span, message));
+                }
+                self.access(Place::Deref(id), Access::Borrow { mutable: mutating }, var.span);
+                self.binding(target).value.clone()
+            }
+            _ if matches!(recv.kind, ExprKind::Deref(_)) => {
+                let diagnostic = not_a_reference(&binding.name, var.span);
+                self.error(diagnostic);
+                Value::Unit
+            }
+            value => {
+                if mutating && !binding.mutable {
+                    let message = format!("`{}` 没有声明为 mut，不能借用为可变", binding.name);
+                    self.error(Diagnostic::new(ErrorCode::NotMutable, recv.span, message));
+                }
+                // 自动借用只在调用期间存在，不会留下借用
+                self.access(Place::Var(id), Access::Borrow { mutable: mutating }, recv.span);
+                value
+            }
+        }
+    }
+}
+
+fn not_a_reference(name: &str, span: Span) -> Diagnostic {
+    Diagnostic::new(ErrorCode::NotAReference, span, format!("`{name}` 不是引用，不能解引用"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ErrorCode, Span, check, run};
+
+    fn span(line: usize, col: usize) -> Span {
+        Span { line, col }
+    }
+
+    fn check_err(source: &str) -> Vec<super::Diagnostic> {
+        check(source).expect_err("应当检查失败")
+    }
+
+    #[test]
+    fn dangling_reference_reports_borrow_drop_and_later_use() {
+        let source = "let r;\n{\n    let s = String::from(\"sui\");\n    r = &s;\n}\nprint(r);\n";
+        let errors = check_err(source);
+        assert_eq!(errors.len(), 1);
+        let error = &errors[0];
+        assert_eq!(error.code, ErrorCode::Dangling);
+        assert_eq!(error.span, span(4, 9));
+        let notes: Vec<Span> = error.notes.iter().map(|(span, _)| *span).collect();
+        assert_eq!(notes, [span(5, 1), span(6, 7)]);
+    }
+
+    #[test]
+    fn use_after_move_points_at_the_move() {
+        let errors = check_err("let s = \"a\";\nlet t = s;\nprint(s);");
+        assert_eq!(errors[0].code, ErrorCode::UseAfterMove);
+        assert_eq!(errors[0].span, span(3, 7));
+        assert_eq!(errors[0].notes[0].0, span(2, 9));
+    }
+
+    #[test]
+    fn conflict_notes_borrow_and_later_use() {
+        let errors = check_err("let mut s = \"a\";\nlet r = &mut s;\nlet q = &mut s;\nprint(r);");
+        assert_eq!(errors[0].code, ErrorCode::SecondMutBorrow);
+        assert_eq!(errors[0].span, span(3, 9));
+        let notes: Vec<Span> = errors[0].notes.iter().map(|(span, _)| *span).collect();
+        assert_eq!(notes, [span(2, 9), span(4, 7)]);
+    }
+
+    #[test]
+    fn borrow_ends_at_last_use() {
+        assert!(check("let mut s = \"a\";\nlet r = &s;\nprint(r);\nlet m = &mut s;\nm.push_str(\"b\");").is_ok());
+    }
+
+    #[test]
+    fn reassigned_holder_releases_old_loan() {
+        let source = "let mut a = 1;\nlet b = 2;\nlet mut r = &a;\nr = &b;\na = 5;\nprint(r, a);";
+        assert_eq!(run(source).expect("通过检查"), ["2 5"]);
+    }
+
+    #[test]
+    fn assignment_must_keep_the_type() {
+        // 曾经能通过检查，执行时引用成环导致栈溢出
+        let errors = check_err("let mut a = 1;\nlet r = &mut a;\n*r = &r;\nprint(a);");
+        assert_eq!(errors[0].code, ErrorCode::Mismatch);
+        assert_eq!(errors[0].span, span(3, 2));
+
+        let errors = check_err("let a = 1;\nlet mut b = &a;\nlet r = &mut b;\n*r = &r;");
+        assert_eq!(errors[0].code, ErrorCode::Mismatch);
+
+        assert_eq!(check_err("let mut n = 1;\nn = \"x\";")[0].code, ErrorCode::Mismatch);
+        assert_eq!(run("let a = 1;\nlet c = 2;\nlet mut b = &a;\nlet r = &mut b;\n*r = &c;\nprint(b);").unwrap(), ["2"]);
+    }
+
+    #[test]
+    fn deferred_initialization_accepts_any_type() {
+        assert_eq!(run("let r;\nlet x = 1;\nr = &x;\nprint(r);").unwrap(), ["1"]);
+    }
+
+    #[test]
+    fn two_mutable_reborrows_conflict() {
+        let source = "let mut s = \"a\";\nlet r1 = &mut s;\nlet r2 = &mut *r1;\nlet r3 = &mut *r1;\nr2.push_str(\"x\");\nr3.push_str(\"y\");";
+        let errors = check_err(source);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::SecondMutBorrow);
+        assert_eq!(errors[0].span, span(4, 10));
+        let notes: Vec<Span> = errors[0].notes.iter().map(|(span, _)| *span).collect();
+        assert_eq!(notes, [span(3, 10), span(5, 1)]);
+    }
+
+    #[test]
+    fn write_through_parent_while_reborrowed() {
+        let errors = check_err("let mut s = \"a\";\nlet r = &mut s;\nlet r2 = &*r;\n*r = \"z\";\nprint(r2);");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::AssignToBorrowed);
+        assert_eq!(errors[0].span, span(4, 2));
+    }
+
+    #[test]
+    fn mutate_through_parent_while_shared_reborrow_lives() {
+        let errors = check_err("let mut s = \"a\";\nlet r = &mut s;\nlet r2 = &*r;\nr.push_str(\"b\");\nprint(r2);");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::BorrowConflict);
+        assert_eq!(errors[0].span, span(4, 1));
+    }
+
+    #[test]
+    fn reborrows_end_at_last_use() {
+        let source = "let mut s = \"a\";\nlet r1 = &mut s;\nlet r2 = &mut *r1;\nr2.push_str(\"x\");\nlet r3 = &mut *r1;\nr3.push_str(\"y\");\nr1.push_str(\"z\");\nprint(s);";
+        assert_eq!(run(source).expect("通过检查"), ["axyz"]);
+        // 再借用仍然沿用 r 对 s 的借用
+        let errors = check_err("let mut s = \"a\";\nlet r = &mut s;\nlet r2 = &*r;\nprint(s);\nprint(r2);");
+        assert_eq!(errors[0].code, ErrorCode::BorrowConflict);
+        // 给 r 重新赋值后，旧的 *r 借用不再和 r 冲突
+        let source = "let mut a = \"a\";\nlet mut b = \"b\";\nlet mut r = &mut a;\nlet r2 = &mut *r;\nr = &mut b;\nr.push_str(\"!\");\nr2.push_str(\"?\");\nprint(a, b);";
+        assert_eq!(run(source).expect("通过检查"), ["a? b!"]);
+    }
+}
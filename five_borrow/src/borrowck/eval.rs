@@ -0,0 +1,151 @@
+// 解释执行: 只运行通过借用检查的程序，所以这里不再做任何检查
+/*
+    每个 let 分配一个槽位，引用就是槽位下标; 移动只是复制值，
+    被移动的变量之后不会再被读到（借用检查保证）。
+*/
+
+use super::parser::{Expr, ExprKind, Program, Stmt};
+
+#[derive(Debug, Clone)]
+enum Value {
+    Unit,
+    Int(i64),
+    Str(String),
+    Ref(usize),
+}
+
+struct Machine {
+    slots: Vec<Value>,
+    scopes: Vec<Vec<(String, usize)>>,
+    output: Vec<String>,
+}
+
+// 返回 print 输出的每一行
+pub fn run(program: &Program) -> Vec<String> {
+    let mut machine = Machine { slots: Vec::new(), scopes: vec![Vec::new()], output: Vec::new() };
+    machine.stmts(&program.stmts);
+    machine.output
+}
+
+impl Machine {
+    fn lookup(&self, name: &str) -> usize {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(n, _)| n == name)
+            .map(|&(_, slot)| slot)
+            .expect("通过借用检查的程序变量都已声明")
+    }
+
+    // 沿引用找到最终的值所在的槽位; 最多走 slots.len() 步，即使引用成环也会停下
+    fn deref(&self, mut slot: usize) -> usize {
+        for _ in 0..self.slots.len() {
+            match self.slots[slot] {
+                Value::Ref(target) => slot = target,
+                _ => break,
+            }
+        }
+        slot
+    }
+
+    fn place(&self, expr: &Expr) -> Option<usize> {
+        match &expr.kind {
+            ExprKind::Var(name) => Some(self.lookup(name)),
+            ExprKind::Deref(inner) => match self.slots[self.place(inner)?] {
+                Value::Ref(target) => Some(target),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn display(&self, value: &Value) -> String {
+        match value {
+            Value::Unit => "()".to_string(),
+            Value::Int(n) => n.to_string(),
+            Value::Str(s) => s.clone(),
+            Value::Ref(slot) => match &self.slots[self.deref(*slot)] {
+                Value::Ref(_) => "<循环引用>".to_string(),
+                value => self.display(value),
+            },
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Let { name, init, .. } => {
+                    let value = init.as_ref().map_or(Value::Unit, |init| self.eval(init));
+                    self.slots.push(value);
+                    let slot = self.slots.len() - 1;
+                    self.scopes.last_mut().expect("至少有一层作用域").push((name.clone(), slot));
+                }
+                Stmt::Assign { name, deref, value, .. } => {
+                    let value = self.eval(value);
+                    let mut slot = self.lookup(name);
+                    if *deref && let Value::Ref(target) = self.slots[slot] {
+                        slot = target;
+                    }
+                    self.slots[slot] = value;
+                }
+                Stmt::Expr(expr) => {
+                    self.eval(expr);
+                }
+                Stmt::Block { stmts, .. } => {
+                    self.scopes.push(Vec::new());
+                    self.stmts(stmts);
+                    self.scopes.pop();
+                }
+            }
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Value {
+        match &expr.kind {
+            ExprKind::Int(n) => Value::Int(*n),
+            ExprKind::Str(s) => Value::Str(s.clone()),
+            ExprKind::Var(name) => self.slots[self.lookup(name)].clone(),
+            ExprKind::Borrow { place, .. } => self.place(place).map_or(Value::Unit, Value::Ref),
+            ExprKind::Deref(_) => self.place(expr).map_or(Value::Unit, |slot| self.slots[slot].clone()),
+            ExprKind::Call { func, args } => {
+                let values: Vec<Value> = args.iter().map(|arg| self.eval(arg)).collect();
+                match func.as_str() {
+                    "print" => {
+                        let line: Vec<String> = values.iter().map(|v| self.display(v)).collect();
+                        self.output.push(line.join(" "));
+                        Value::Unit
+                    }
+                    "drop" => Value::Unit,
+                    _ => values.into_iter().next().unwrap_or(Value::Unit),
+                }
+            }
+            ExprKind::Method { recv, method, args } => {
+                let args: Vec<Value> = args.iter().map(|arg| self.eval(arg)).collect();
+                let slot = match self.place(recv) {
+                    Some(slot) => self.deref(slot),
+                    None => {
+                        let value = self.eval(recv);
+                        self.slots.push(value);
+                        self.slots.len() - 1
+                    }
+                };
+                let arg = args.first().map(|v| self.display(v)).unwrap_or_default();
+                let receiver = &mut self.slots[slot];
+                match (method.as_str(), receiver) {
+                    ("clone", receiver) => receiver.clone(),
+                    ("len", Value::Str(s)) => Value::Int(s.len() as i64),
+                    ("push_str" | "push", Value::Str(s)) => {
+                        s.push_str(&arg);
+                        Value::Unit
+                    }
+                    ("clear", Value::Str(s)) => {
+                        s.clear();
+                        Value::Unit
+                    }
+                    _ => Value::Unit,
+                }
+            }
+        }
+    }
+}
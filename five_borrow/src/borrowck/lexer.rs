@@ -0,0 +1,124 @@
+// 词法分析: 源码 → (Token, Span) 序列，// 之后到行尾为注释，:: 只用于 String::from 这类函数名
+
+use super::{Diagnostic, ErrorCode, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Let,
+    Mut,
+    Amp,
+    Star,
+    Eq,
+    Semi,
+    Comma,
+    Dot,
+    PathSep,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Eof,
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Span)>, Diagnostic> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let (mut line, mut col) = (1, 1);
+
+    while let Some(&c) = chars.peek() {
+        let span = Span { line, col };
+        let mut bump = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let c = chars.next();
+            if c == Some('\n') {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+            c
+        };
+
+        if c.is_whitespace() {
+            bump(&mut chars);
+            continue;
+        }
+        if c == '/' {
+            bump(&mut chars);
+            if chars.peek() != Some(&'/') {
+                return Err(Diagnostic::new(ErrorCode::Syntax, span, "不支持的字符 /"));
+            }
+            while chars.peek().is_some_and(|&c| c != '\n') {
+                bump(&mut chars);
+            }
+            continue;
+        }
+        if c == ':' {
+            bump(&mut chars);
+            if chars.peek() != Some(&':') {
+                return Err(Diagnostic::new(ErrorCode::Syntax, span, "不支持的字符 :"));
+            }
+            bump(&mut chars);
+            tokens.push((Token::PathSep, span));
+            continue;
+        }
+        let token = match c {
+            '&' => Token::Amp,
+            '*' => Token::Star,
+            '=' => Token::Eq,
+            ';' => Token::Semi,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '"' => {
+                bump(&mut chars);
+                let mut text = String::new();
+                loop {
+                    match bump(&mut chars) {
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(Diagnostic::new(ErrorCode::Syntax, span, "字符串没有结束")),
+                    }
+                }
+                tokens.push((Token::Str(text), span));
+                continue;
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    bump(&mut chars);
+                }
+                let n = digits
+                    .parse()
+                    .map_err(|_| Diagnostic::new(ErrorCode::Syntax, span, format!("整数过大: {digits}")))?;
+                tokens.push((Token::Int(n), span));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&w) = chars.peek().filter(|w| w.is_alphanumeric() || **w == '_') {
+                    word.push(w);
+                    bump(&mut chars);
+                }
+                let token = match word.as_str() {
+                    "let" => Token::Let,
+                    "mut" => Token::Mut,
+                    _ => Token::Ident(word),
+                };
+                tokens.push((token, span));
+                continue;
+            }
+            other => return Err(Diagnostic::new(ErrorCode::Syntax, span, format!("不支持的字符 {other}"))),
+        };
+        bump(&mut chars);
+        tokens.push((token, span));
+    }
+    tokens.push((Token::Eof, Span { line, col }));
+    Ok(tokens)
+}
@@ -0,0 +1,282 @@
+// 语法分析: 递归下降，生成语法树
+/*
+    program := stmt*
+    stmt    := "let" "mut"? IDENT ("=" expr)? ";"
+             | "*"? IDENT "=" expr ";"
+             | "{" stmt* "}"
+             | expr ";"
+    expr    := "&" "mut"? expr | "*" expr | postfix
+    postfix := primary ("." IDENT "(" args ")")*
+    primary := INT | STRING | IDENT ("::" IDENT)* ("(" args ")")? | "(" expr ")"
+*/
+
+use super::lexer::{Token, tokenize};
+use super::{Diagnostic, ErrorCode, Span};
+
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub stmts: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Let { name: String, span: Span, mutable: bool, init: Option<Expr> },
+    // deref 为 true 时是 *name = value
+    Assign { name: String, span: Span, deref: bool, value: Expr },
+    Expr(Expr),
+    // close 是右花括号的位置，块内变量在这里释放
+    Block { stmts: Vec<Stmt>, close: Span },
+}
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
+    Int(i64),
+    Str(String),
+    Var(String),
+    Borrow { mutable: bool, place: Box<Expr> },
+    Deref(Box<Expr>),
+    Call { func: String, args: Vec<Expr> },
+    Method { recv: Box<Expr>, method: String, args: Vec<Expr> },
+}
+
+impl Expr {
+    // 能被借用、解引用的位置: 变量或 *变量
+    pub fn is_place(&self) -> bool {
+        match &self.kind {
+            ExprKind::Var(_) => true,
+            ExprKind::Deref(inner) => matches!(inner.kind, ExprKind::Var(_)),
+            _ => false,
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Program, Diagnostic> {
+    let mut parser = Parser { tokens: tokenize(source)?, pos: 0 };
+    let mut stmts = Vec::new();
+    while parser.peek() != &Token::Eof {
+        stmts.push(parser.stmt()?);
+    }
+    Ok(Program { stmts })
+}
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.pos + offset).min(last)].0
+    }
+
+    fn span(&self) -> Span {
+        self.tokens[self.pos].1
+    }
+
+    fn next(&mut self) -> (Token, Span) {
+        let token = self.tokens[self.pos].clone();
+        if token.0 != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<Span, Diagnostic> {
+        let span = self.span();
+        if self.eat(&token) { Ok(span) } else { Err(self.error(format!("这里应为 {what}"))) }
+    }
+
+    fn ident(&mut self) -> Result<(String, Span), Diagnostic> {
+        match self.next() {
+            (Token::Ident(name), span) => Ok((name, span)),
+            (_, span) => Err(Diagnostic::new(ErrorCode::Syntax, span, "这里应为变量名")),
+        }
+    }
+
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(ErrorCode::Syntax, self.span(), message)
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        match self.peek() {
+            Token::Let => {
+                self.next();
+                let mutable = self.eat(&Token::Mut);
+                let (name, span) = self.ident()?;
+                let init = if self.eat(&Token::Eq) { Some(self.expr()?) } else { None };
+                self.expect(Token::Semi, ";")?;
+                Ok(Stmt::Let { name, span, mutable, init })
+            }
+            Token::LBrace => {
+                self.next();
+                let mut stmts = Vec::new();
+                while !matches!(self.peek(), Token::RBrace | Token::Eof) {
+                    stmts.push(self.stmt()?);
+                }
+                let close = self.expect(Token::RBrace, "}")?;
+                Ok(Stmt::Block { stmts, close })
+            }
+            Token::Ident(_) if self.peek_at(1) == &Token::Eq => {
+                let (name, span) = self.ident()?;
+                self.next();
+                let value = self.expr()?;
+                self.expect(Token::Semi, ";")?;
+                Ok(Stmt::Assign { name, span, deref: false, value })
+            }
+            Token::Star if matches!(self.peek_at(1), Token::Ident(_)) && self.peek_at(2) == &Token::Eq => {
+                self.next();
+                let (name, span) = self.ident()?;
+                self.next();
+                let value = self.expr()?;
+                self.expect(Token::Semi, ";")?;
+                Ok(Stmt::Assign { name, span, deref: true, value })
+            }
+            _ => {
+                let expr = self.expr()?;
+                self.expect(Token::Semi, ";")?;
+                Ok(Stmt::Expr(expr))
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, Diagnostic> {
+        let span = self.span();
+        if self.eat(&Token::Amp) {
+            let mutable = self.eat(&Token::Mut);
+            let place = self.expr()?;
+            if !place.is_place() {
+                return Err(Diagnostic::new(ErrorCode::Syntax, place.span, "只能借用变量或 *变量"));
+            }
+            return Ok(Expr { kind: ExprKind::Borrow { mutable, place: Box::new(place) }, span });
+        }
+        if self.eat(&Token::Star) {
+            let inner = self.expr()?;
+            if !matches!(inner.kind, ExprKind::Var(_)) {
+                return Err(Diagnostic::new(ErrorCode::Syntax, inner.span, "只能解引用变量"));
+            }
+            return Ok(Expr { kind: ExprKind::Deref(Box::new(inner)), span });
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Diagnostic> {
+        let mut expr = self.primary()?;
+        while self.peek() == &Token::Dot {
+            let dot = self.next().1;
+            let (method, _) = self.ident()?;
+            self.expect(Token::LParen, "(")?;
+            let args = self.args()?;
+            expr = Expr { kind: ExprKind::Method { recv: Box::new(expr), method, args }, span: dot };
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, Diagnostic> {
+        let (token, span) = self.next();
+        let kind = match token {
+            Token::Int(n) => ExprKind::Int(n),
+            Token::Str(s) => ExprKind::Str(s),
+            Token::LParen => {
+                let expr = self.expr()?;
+                self.expect(Token::RParen, ")")?;
+                return Ok(expr);
+            }
+            Token::Ident(mut name) => {
+                while self.eat(&Token::PathSep) {
+                    name.push_str("::");
+                    name.push_str(&self.ident()?.0);
+                }
+                if self.eat(&Token::LParen) {
+                    ExprKind::Call { func: name, args: self.args()? }
+                } else if name.contains("::") {
+                    return Err(Diagnostic::new(ErrorCode::Syntax, span, format!("{name} 后面应为 (")));
+                } else {
+                    ExprKind::Var(name)
+                }
+            }
+            _ => return Err(Diagnostic::new(ErrorCode::Syntax, span, "这里应为表达式")),
+        };
+        Ok(Expr { kind, span })
+    }
+
+    // 左括号之后的参数列表，包括右括号
+    fn args(&mut self) -> Result<Vec<Expr>, Diagnostic> {
+        let mut args = Vec::new();
+        while self.peek() != &Token::RParen {
+            args.push(self.expr()?);
+            if !self.eat(&Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen, ")")?;
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_are_one_based_line_and_column() {
+        let program = parse("let x = 1;\n  print(&x);").unwrap();
+        let Stmt::Let { span, .. } = &program.stmts[0] else { panic!("应为 let") };
+        assert_eq!(*span, Span { line: 1, col: 5 });
+        let Stmt::Expr(Expr { kind: ExprKind::Call { args, .. }, span }) = &program.stmts[1] else {
+            panic!("应为函数调用")
+        };
+        assert_eq!(*span, Span { line: 2, col: 3 });
+        assert_eq!(args[0].span, Span { line: 2, col: 9 });
+    }
+
+    #[test]
+    fn block_records_closing_brace() {
+        let program = parse("{\n  let a = 1;\n}").unwrap();
+        let Stmt::Block { stmts, close } = &program.stmts[0] else { panic!("应为块") };
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(*close, Span { line: 3, col: 1 });
+    }
+
+    #[test]
+    fn syntax_errors_have_positions() {
+        let error = parse("let s = \"a\"\nprint(s);").unwrap_err();
+        assert_eq!(error.code, ErrorCode::Syntax);
+        assert_eq!(error.span, Span { line: 2, col: 1 });
+
+        // 方法调用的位置是 . 所在处
+        let error = parse("let r = &s.len();").unwrap_err();
+        assert_eq!(error.span, Span { line: 1, col: 11 });
+
+        let error = parse("let s = \"open;").unwrap_err();
+        assert_eq!(error.span, Span { line: 1, col: 9 });
+    }
+
+    #[test]
+    fn comments_and_paths() {
+        let program = parse("// 注释\nlet s = String::from(\"x\"); // 行尾注释").unwrap();
+        let Stmt::Let { init: Some(Expr { kind: ExprKind::Call { func, .. }, .. }), .. } = &program.stmts[0] else {
+            panic!("应为 let 调用")
+        };
+        assert_eq!(func, "String::from");
+    }
+}
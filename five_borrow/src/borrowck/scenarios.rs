@@ -0,0 +1,265 @@
+// 课程中的借用示例: main.rs 里注释掉的报错代码改写成迷你语言，附上预期结果
+/*
+    每个场景要么通过检查并输出指定的行，要么报出指定的错误码;
+    源码顶格书写，诊断中的行列号和这里看到的一致;
+    cargo test 和 cargo run --bin borrowck 都会逐个验证，也可以用 check / run 单独运行。
+*/
+
+use super::{ErrorCode, run};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Expect {
+    // 通过检查，print 输出这些行
+    Output(&'static [&'static str]),
+    // 第一个错误的错误码
+    Error(ErrorCode),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Scenario {
+    pub name: &'static str,
+    // 对应的课程位置
+    pub origin: &'static str,
+    pub source: &'static str,
+    pub expect: Expect,
+}
+
+impl Scenario {
+    // 结果和预期不符时返回说明
+    pub fn verify(&self) -> Result<(), String> {
+        match (run(self.source), self.expect) {
+            (Ok(output), Expect::Output(expected)) if output == expected => Ok(()),
+            (Err(errors), Expect::Error(code)) if errors[0].code == code => Ok(()),
+            (Ok(output), _) => Err(format!("通过了检查，输出 {output:?}")),
+            (Err(errors), _) => Err(errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n")),
+        }
+    }
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    // ---------- 所有权 ----------
+    Scenario {
+        name: "移动后使用",
+        origin: "four_ownership 3、复制 Copy",
+        source: r#"let s1 = String::from("jamie");
+let s2 = s1;
+print(s1);
+"#,
+        expect: Expect::Error(ErrorCode::UseAfterMove),
+    },
+    Scenario {
+        name: "传参转移所有权",
+        origin: "four_ownership 2、移动 Move",
+        source: r#"let s = String::from("jamie!");
+takes_ownership(s);
+print(s);
+"#,
+        expect: Expect::Error(ErrorCode::UseAfterMove),
+    },
+    Scenario {
+        name: "整数是 Copy",
+        origin: "four_ownership 3、复制 Copy",
+        source: r#"let x = 5;
+let y = x;
+print(x, y);
+"#,
+        expect: Expect::Output(&["5 5"]),
+    },
+    Scenario {
+        name: "clone 深拷贝",
+        origin: "four_ownership 3、复制 Copy",
+        source: r#"let s1 = String::from("jamie");
+let s2 = s1.clone();
+print(s1, s2);
+"#,
+        expect: Expect::Output(&["jamie jamie"]),
+    },
+    Scenario {
+        name: "所有权交回",
+        origin: "four_ownership 6、所有权与函数返回",
+        source: r#"let s2 = String::from("sui");
+let s3 = takes_and_gives_back(s2);
+print(s3);
+"#,
+        expect: Expect::Output(&["sui"]),
+    },
+    // ---------- 借用 ----------
+    Scenario {
+        name: "多个不可变借用",
+        origin: "five_borrow 1）不可变借用",
+        source: r#"let s = String::from("Jamie");
+let r1 = &s;
+let r2 = &s;
+print(r1, r2);
+print(s);
+"#,
+        expect: Expect::Output(&["Jamie Jamie", "Jamie"]),
+    },
+    Scenario {
+        name: "可变借用修改",
+        origin: "five_borrow 2）可变借用",
+        source: r#"let mut s2 = String::from("Jamie");
+let r = &mut s2;
+r.push_str(", world");
+print(s2);
+"#,
+        expect: Expect::Output(&["Jamie, world"]),
+    },
+    Scenario {
+        name: "通过 & 引用赋值",
+        origin: "five_borrow main: *y = 10",
+        source: r#"let x = 5;
+let y = &x;
+*y = 10;
+"#,
+        expect: Expect::Error(ErrorCode::WriteThroughShared),
+    },
+    Scenario {
+        name: "可变借用之后不再使用",
+        origin: "five_borrow main: let r3 = &mut s3",
+        source: r#"let mut s3 = String::from("Jamie");
+let r3 = &mut s3;
+print(s3);
+"#,
+        expect: Expect::Output(&["Jamie"]),
+    },
+    Scenario {
+        name: "可变借用期间读取",
+        origin: "five_borrow main: println!(\"{}, {}\", s3, r3)",
+        source: r#"let mut s3 = String::from("Jamie");
+let r3 = &mut s3;
+print(s3, r3);
+"#,
+        expect: Expect::Error(ErrorCode::BorrowConflict),
+    },
+    Scenario {
+        name: "两个可变借用",
+        origin: "five_borrow main: let r2 = &mut s3",
+        source: r#"let mut s3 = String::from("Jamie");
+let r3 = &mut s3;
+let r2 = &mut s3;
+print(r2, r3);
+"#,
+        expect: Expect::Error(ErrorCode::SecondMutBorrow),
+    },
+    Scenario {
+        name: "不可变借用期间可变借用",
+        origin: "five_borrow main: let r6 = &mut s4",
+        source: r#"let mut s4 = String::from("sonic");
+let r4 = &s4;
+let r5 = &s4;
+let r6 = &mut s4;
+print(r4, r5);
+"#,
+        expect: Expect::Error(ErrorCode::BorrowConflict),
+    },
+    Scenario {
+        name: "最后一次使用后借用结束",
+        origin: "four_ownership 4、引用与借用",
+        source: r#"let mut s = String::from("solana");
+let r1 = &s;
+let r2 = &s;
+print(r1, r2);
+let r3 = &mut s;
+r3.push_str(" sui");
+print(s);
+"#,
+        expect: Expect::Output(&["solana solana", "solana sui"]),
+    },
+    Scenario {
+        name: "切片借用期间修改",
+        origin: "five_borrow main: s5.clear()",
+        source: r#"let mut s5 = String::from("sonic bnb");
+let word = first_word(&s5);
+s5.clear();
+print(word);
+"#,
+        expect: Expect::Error(ErrorCode::BorrowConflict),
+    },
+    Scenario {
+        name: "所有者没有 mut",
+        origin: "five_borrow 总结: 未mut变量",
+        source: r#"let s = String::from("solana");
+s.push_str(" sui");
+"#,
+        expect: Expect::Error(ErrorCode::NotMutable),
+    },
+    Scenario {
+        name: "借用期间移动",
+        origin: "five_borrow 6）借用后不能移动",
+        source: r#"let s = String::from("solana");
+let r = &s;
+let t = s;
+print(r);
+"#,
+        expect: Expect::Error(ErrorCode::MoveWhileBorrowed),
+    },
+    Scenario {
+        name: "借用期间赋值",
+        origin: "five_borrow 3）借用规则",
+        source: r#"let mut n = 1;
+let r = &n;
+n = 2;
+print(r);
+"#,
+        expect: Expect::Error(ErrorCode::AssignToBorrowed),
+    },
+    // ---------- 作用域 ----------
+    Scenario {
+        name: "悬垂引用",
+        origin: "five_borrow 4）悬垂引用",
+        source: r#"let r;
+{
+    let s = String::from("sui");
+    r = &s;
+}
+print(r);
+"#,
+        expect: Expect::Error(ErrorCode::Dangling),
+    },
+    Scenario {
+        name: "用块限制借用范围",
+        origin: "five_borrow 总结: 最小借用作用域",
+        source: r#"let mut s = String::from("hi");
+{
+    let r = &mut s;
+    r.push_str("!");
+}
+let r2 = &mut s;
+r2.push_str("?");
+print(s);
+"#,
+        expect: Expect::Output(&["hi!?"]),
+    },
+    Scenario {
+        name: "遮蔽",
+        origin: "one_base 变量遮蔽",
+        source: r#"let s = String::from("sui");
+let s = s.len();
+print(s);
+"#,
+        expect: Expect::Output(&["3"]),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_scenario_matches_expectation() {
+        for scenario in SCENARIOS {
+            if let Err(reason) = scenario.verify() {
+                panic!("{} ({}):\n{reason}", scenario.name, scenario.origin);
+            }
+        }
+    }
+
+    #[test]
+    fn scenario_names_are_unique() {
+        let mut names: Vec<&str> = SCENARIOS.iter().map(|s| s.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), SCENARIOS.len());
+    }
+}
@@ -0,0 +1,253 @@
+// ---------------- 字符串驻留 Interner ----------------
+/*
+    what: 相同的字符串只存一份，用 4 字节的 Symbol 代替，比较、哈希、复制都是整数操作。
+    how:
+        - Interner: 单线程，intern(&mut self)，resolve 借出 &str;
+        - SharedInterner: RwLock 包装，可在线程间共享; 先读锁查找，未命中再加写锁插入，
+          resolve 返回 Arc<str>，不受锁的生命周期限制;
+        - StaticInterner: 字符串 Box::leak 成 &'static str，永不释放，
+          resolve 直接返回 &'static str，和 static_ref 一样可以随处传递;
+          只适合长期运行、标识符集合有限的进程;
+        - 性能对比: cargo run --release --bin intern_bench
+*/
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+// 三种 Interner 共用的表，S 是字符串的存储方式
+#[derive(Debug, Default)]
+struct Table<S> {
+    ids: HashMap<S, Symbol>,
+    strings: Vec<S>,
+}
+
+impl<S: Borrow<str> + Clone + Hash + Eq> Table<S> {
+    fn get(&self, s: &str) -> Option<Symbol> {
+        self.ids.get(s).copied()
+    }
+
+    fn insert(&mut self, s: &str, store: impl FnOnce(&str) -> S) -> Symbol {
+        if let Some(sym) = self.get(s) {
+            return sym;
+        }
+        let sym = Symbol(u32::try_from(self.strings.len()).expect("符号数超过 u32::MAX"));
+        let stored = store(s);
+        self.strings.push(stored.clone());
+        self.ids.insert(stored, sym);
+        sym
+    }
+
+    fn resolve(&self, sym: Symbol) -> Option<&S> {
+        self.strings.get(sym.0 as usize)
+    }
+}
+
+// ---------- 单线程 ----------
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    table: Table<Arc<str>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        self.table.insert(s, |s| Arc::from(s))
+    }
+
+    // 只查找，不插入
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.table.get(s)
+    }
+
+    pub fn resolve(&self, sym: Symbol) -> Option<&str> {
+        self.table.resolve(sym).map(|s| &**s)
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.table.strings.iter().enumerate().map(|(i, s)| (Symbol(i as u32), &**s))
+    }
+}
+
+// ---------- 多线程 ----------
+
+#[derive(Debug, Default)]
+pub struct SharedInterner {
+    table: RwLock<Table<Arc<str>>>,
+}
+
+impl SharedInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&self, s: &str) -> Symbol {
+        // 绝大多数是重复的标识符，读锁就够了
+        if let Some(sym) = self.table.read().unwrap_or_else(|e| e.into_inner()).get(s) {
+            return sym;
+        }
+        self.table.write().unwrap_or_else(|e| e.into_inner()).insert(s, |s| Arc::from(s))
+    }
+
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).get(s)
+    }
+
+    pub fn resolve(&self, sym: Symbol) -> Option<Arc<str>> {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).resolve(sym).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ---------- 泄漏为 'static ----------
+
+#[derive(Debug, Default)]
+pub struct StaticInterner {
+    table: RwLock<Table<&'static str>>,
+}
+
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.to_owned().into_boxed_str())
+}
+
+impl StaticInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&self, s: &str) -> Symbol {
+        if let Some(sym) = self.table.read().unwrap_or_else(|e| e.into_inner()).get(s) {
+            return sym;
+        }
+        self.table.write().unwrap_or_else(|e| e.into_inner()).insert(s, leak)
+    }
+
+    // 相同内容总是返回同一个 &'static str，可以用指针比较
+    pub fn intern_static(&self, s: &str) -> &'static str {
+        let sym = self.intern(s);
+        self.resolve(sym).expect("刚插入的符号一定存在")
+    }
+
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).get(s)
+    }
+
+    pub fn resolve(&self, sym: Symbol) -> Option<&'static str> {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).resolve(sym).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.read().unwrap_or_else(|e| e.into_inner()).strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn same_string_gets_same_symbol() {
+        let mut interner = Interner::new();
+        let a = interner.intern("alpha");
+        let b = interner.intern("beta");
+        assert_eq!(interner.intern("alpha"), a);
+        assert_ne!(a, b);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("beta"), Some(b));
+        assert_eq!(interner.get("gamma"), None);
+    }
+
+    #[test]
+    fn resolve_returns_original_text() {
+        let mut interner = Interner::new();
+        let words = ["", "alpha", "你好", "alpha\0"];
+        let symbols: Vec<Symbol> = words.iter().map(|w| interner.intern(w)).collect();
+        for (sym, word) in symbols.iter().zip(words) {
+            assert_eq!(interner.resolve(*sym), Some(word));
+        }
+        assert_eq!(interner.resolve(Symbol(99)), None);
+        let listed: Vec<&str> = interner.iter().map(|(_, s)| s).collect();
+        assert_eq!(listed, words);
+    }
+
+    #[test]
+    fn concurrent_intern_agrees_on_symbols() {
+        let interner = SharedInterner::new();
+        let words: Vec<String> = (0..200).map(|i| format!("w{i}")).collect();
+        let results: Vec<Vec<Symbol>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|t| {
+                    let (interner, words) = (&interner, &words);
+                    // 每个线程以不同的顺序插入，制造写锁竞争
+                    scope.spawn(move || {
+                        let mut order: Vec<usize> = (0..words.len()).collect();
+                        order.rotate_left(t * 25);
+                        let mut symbols = vec![Symbol(0); words.len()];
+                        for i in order {
+                            symbols[i] = interner.intern(&words[i]);
+                        }
+                        symbols
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(interner.len(), words.len());
+        for symbols in &results {
+            assert_eq!(symbols, &results[0]);
+        }
+        for (sym, word) in results[0].iter().zip(&words) {
+            assert_eq!(interner.resolve(*sym).as_deref(), Some(word.as_str()));
+        }
+    }
+
+    #[test]
+    fn static_interner_returns_the_same_pointer() {
+        let interner = StaticInterner::new();
+        let a = interner.intern_static("alpha");
+        let b = interner.intern_static(&String::from("alpha"));
+        assert!(std::ptr::eq(a, b));
+        assert_eq!(interner.len(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+// ---------------- five_borrow 库 ----------------
+// 借用课程的延伸，main.rs 继续保留课程示例
+
+pub mod borrowck;
+pub mod interner;
+pub mod pointer;
+pub mod rope;
@@ -61,24 +61,21 @@ fn static_ref() -> &'static str {
 }
 
 // 5）引用与切片: 切片是引用的子集，数组或字符串的部分;
+// 返回的单词借用 s，按 Unicode 空白、标点切分（不只是 b' '），见 six_slices::words
 fn first_word(s: &str) -> &str {
-    let bytes = s.as_bytes();
-    for (i, &item) in bytes.iter().enumerate() {
-        // 检查当前是否有空格字符串，有则将第一个单词返回
-        if item == b' ' {
-            return &s[0..i]
-        }
-    }
-    &s[..]
+    six_slices::words::first_word(s)
 }
 
 // 6）引用与所有权交互
 /* 
     - 借用后不能移动: 借用存在时，所有这不能被移动;
     - 解引用强制: 某些操作需要 * ，但方法调用隐式解引用;
-    - Deref trait: 自定义类型可以实现Deref以像引用一样行为;
+    - Deref trait: 自定义类型可以实现Deref以像引用一样行为; 示例见 pointer.rs 的 MyBox、Validated;
 */
 
+use five_borrow::pointer::{MyBox, NonEmpty, NonEmptyString, Percent, Validated};
+use five_borrow::rope::Rope;
+
 fn main() {
 
     let x = 5;
@@ -105,6 +102,13 @@ fn main() {
     append_world(&mut s2);
     println!("s2修改后值: {s2}");
 
+    // 大文本中间频繁插入时用 Rope 代替 String
+    let mut rope = Rope::from("Jamie");
+    rope.append(", how do you do?");
+    rope.insert(5, "，你好").expect("偏移在范围内");
+    println!("rope: {rope}");
+
+    // 下面注释掉的报错示例都改写在 borrowck/scenarios.rs 中: cargo run --bin borrowck
     // 注意：借用期间，s3不允许被其他方式访问!
     let mut s3 = String::from("Jamie");
     let r3 = &mut s3;  // 可变被借用了
@@ -137,7 +141,26 @@ fn main() {
     let s6 = String::from("sui eth!");
     let r6 = &s6;
     println!("{}", r6.len()); // 隐式解引用: *r6.len()
-    
+
+    // 自定义类型实现 Deref 后，* 和自动转换同样适用
+    let b = MyBox::new(5);
+    println!("*b = {}", *b);  // 等价于 *(b.deref())
+
+    let name = NonEmptyString::new(String::from("sui eth")).expect("非空字符串");
+    print_length(&name);  // &Validated<String, _> → &String
+    println!("word: {}", first_word(&name));  // 继续转换为 &str
+    // NonEmptyString::new(String::new());  // Err: 不能为空
+
+    let mut tags = Validated::<Vec<&str>, NonEmpty>::new(vec!["sui", "eth"]).expect("非空列表");
+    let all: &[&str] = &tags;  // &Validated<Vec<T>, _> → &[T]
+    println!("tags: {}", all.join(", "));
+    tags.edit().clear();  // 守卫释放时重新校验，清空不合法，修改被丢弃
+    println!("tags 仍为: {:?}", *tags);
+
+    let mut progress = Percent::new(80).expect("0..=100");
+    if let Err(e) = progress.update(|p| *p += 30) {
+        println!("progress 保持 {progress}: {e}");
+    }
 }
 
 
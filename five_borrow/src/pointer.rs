@@ -0,0 +1,303 @@
+// ---------------- 自定义智能指针 Deref / DerefMut ----------------
+/*
+    what: 课程 "6）引用与所有权交互" 提到自定义类型可以实现 Deref，像引用一样使用，这里给出两个例子。
+        - MyBox<T>: 最小的智能指针，*b 等价于 *(b.deref());
+        - Validated<T, V>: 只能通过校验器 V 构造，之后始终满足 V 的约束，读取时像 &T 一样使用;
+    how:
+        - 只实现 Deref，不直接实现 DerefMut: 否则 *v = 任意值 会绕过校验;
+          修改要通过 edit() 拿到 Edit 守卫，守卫实现 DerefMut，改的是一份副本，
+          离开作用域时重新校验，合法才写回，不合法就丢弃; 想知道是否成功用 commit();
+          即使 mem::forget 了守卫，原值也没有被改动过;
+        - Deref 强制转换: &Validated<String, _> → &String → &str，
+          &Validated<Vec<T>, _> → &Vec<T> → &[T]，可以直接传给接收 &str / &[T] 的函数;
+        - 校验器是零大小的标记类型，约束写在类型里: Validated<i64, Bounded<0, 100>>;
+*/
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+// ---------- MyBox ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MyBox<T>(T);
+
+impl<T> MyBox<T> {
+    pub fn new(value: T) -> MyBox<T> {
+        MyBox(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for MyBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MyBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// ---------- 校验器 ----------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Empty,
+    OutOfRange { value: i128, min: i64, max: i64 },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::Empty => write!(f, "不能为空"),
+            ValidationError::OutOfRange { value, min, max } => write!(f, "{value} 不在 {min}..={max} 范围内"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub trait Validator<T> {
+    fn validate(value: &T) -> Result<(), ValidationError>;
+}
+
+// 非空: 字符串、Vec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonEmpty;
+
+impl Validator<String> for NonEmpty {
+    fn validate(value: &String) -> Result<(), ValidationError> {
+        if value.is_empty() { Err(ValidationError::Empty) } else { Ok(()) }
+    }
+}
+
+impl<T> Validator<Vec<T>> for NonEmpty {
+    fn validate(value: &Vec<T>) -> Result<(), ValidationError> {
+        if value.is_empty() { Err(ValidationError::Empty) } else { Ok(()) }
+    }
+}
+
+// 整数在 MIN..=MAX 之间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounded<const MIN: i64, const MAX: i64>;
+
+macro_rules! impl_bounded {
+    ($($int:ty),*) => {$(
+        impl<const MIN: i64, const MAX: i64> Validator<$int> for Bounded<MIN, MAX> {
+            fn validate(value: &$int) -> Result<(), ValidationError> {
+                let value = *value as i128;
+                if (MIN as i128..=MAX as i128).contains(&value) {
+                    Ok(())
+                } else {
+                    Err(ValidationError::OutOfRange { value, min: MIN, max: MAX })
+                }
+            }
+        }
+    )*};
+}
+
+impl_bounded!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+
+// ---------- Validated ----------
+
+pub struct Validated<T, V> {
+    value: T,
+    validator: PhantomData<V>,
+}
+
+pub type NonEmptyString = Validated<String, NonEmpty>;
+pub type Percent = Validated<u8, Bounded<0, 100>>;
+
+impl<T, V: Validator<T>> Validated<T, V> {
+    pub fn new(value: T) -> Result<Self, ValidationError> {
+        V::validate(&value)?;
+        Ok(Validated { value, validator: PhantomData })
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    // 修改期间可以暂时不合法，守卫释放时重新校验
+    pub fn edit(&mut self) -> Edit<'_, T, V>
+    where
+        T: Clone,
+    {
+        let draft = Some(self.value.clone());
+        Edit { target: self, draft }
+    }
+
+    // 在闭包中修改，不合法时保持原值并返回错误
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) -> Result<(), ValidationError>
+    where
+        T: Clone,
+    {
+        let mut edit = self.edit();
+        f(&mut edit);
+        edit.commit()
+    }
+}
+
+impl<T, V> Deref for Validated<T, V> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, V> AsRef<T> for Validated<T, V> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+// 手动实现: 派生会要求 V 也实现这些 trait
+impl<T: Clone, V> Clone for Validated<T, V> {
+    fn clone(&self) -> Self {
+        Validated { value: self.value.clone(), validator: PhantomData }
+    }
+}
+
+impl<T: PartialEq, V> PartialEq for Validated<T, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq, V> Eq for Validated<T, V> {}
+
+impl<T: fmt::Debug, V> fmt::Debug for Validated<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Validated").field(&self.value).finish()
+    }
+}
+
+impl<T: fmt::Display, V> fmt::Display for Validated<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+// ---------- 修改守卫 ----------
+
+pub struct Edit<'a, T, V: Validator<T>> {
+    target: &'a mut Validated<T, V>,
+    // 正在修改的副本，校验通过才写回 target; commit 或 drop 之后为 None
+    draft: Option<T>,
+}
+
+impl<T, V: Validator<T>> Edit<'_, T, V> {
+    // 立即校验，不合法时丢弃修改并返回错误
+    pub fn commit(mut self) -> Result<(), ValidationError> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Result<(), ValidationError> {
+        let Some(draft) = self.draft.take() else { return Ok(()) };
+        V::validate(&draft)?;
+        self.target.value = draft;
+        Ok(())
+    }
+}
+
+impl<T, V: Validator<T>> Deref for Edit<'_, T, V> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.draft.as_ref().expect("提交前副本一直存在")
+    }
+}
+
+impl<T, V: Validator<T>> DerefMut for Edit<'_, T, V> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.draft.as_mut().expect("提交前副本一直存在")
+    }
+}
+
+impl<T, V: Validator<T>> Drop for Edit<'_, T, V> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn char_count(s: &str) -> usize {
+        s.chars().count()
+    }
+
+    fn sum(values: &[i32]) -> i32 {
+        values.iter().sum()
+    }
+
+    #[test]
+    fn construction_is_validated() {
+        assert_eq!(NonEmptyString::new(String::new()).unwrap_err(), ValidationError::Empty);
+        assert_eq!(Percent::new(101).unwrap_err(), ValidationError::OutOfRange { value: 101, min: 0, max: 100 });
+        assert_eq!(*Percent::new(100).unwrap(), 100);
+        assert!(Validated::<i32, Bounded<-5, 5>>::new(-6).is_err());
+    }
+
+    #[test]
+    fn deref_coerces_to_str_and_slice() {
+        let name = NonEmptyString::new("你好 sui".to_string()).unwrap();
+        assert_eq!(char_count(&name), 6);
+        let numbers = Validated::<Vec<i32>, NonEmpty>::new(vec![1, 2, 3]).unwrap();
+        assert_eq!(sum(&numbers), 6);
+        assert_eq!(numbers.len(), 3);
+
+        let mut b = MyBox::new(String::from("box"));
+        b.push('!');
+        assert_eq!(char_count(&b), 4);
+    }
+
+    #[test]
+    fn invalid_edit_keeps_the_old_value() {
+        let mut name = NonEmptyString::new("sui".to_string()).unwrap();
+        name.edit().clear();
+        assert_eq!(*name, "sui");
+
+        let mut edit = name.edit();
+        edit.clear();
+        assert_eq!(edit.commit(), Err(ValidationError::Empty));
+        assert_eq!(*name, "sui");
+
+        let mut percent = Percent::new(80).unwrap();
+        assert!(percent.update(|p| *p += 30).is_err());
+        assert_eq!(*percent, 80);
+    }
+
+    #[test]
+    fn valid_edit_is_written_back() {
+        let mut name = NonEmptyString::new("sui".to_string()).unwrap();
+        {
+            let mut edit = name.edit();
+            edit.clear();
+            // 中间状态可以暂时不合法
+            edit.push_str("eth");
+        }
+        assert_eq!(*name, "eth");
+        assert_eq!(name.update(|s| s.push('!')), Ok(()));
+        assert_eq!(*name, "eth!");
+    }
+
+    #[test]
+    fn forgotten_guard_cannot_break_the_invariant() {
+        let mut name = NonEmptyString::new("sui".to_string()).unwrap();
+        let mut edit = name.edit();
+        edit.clear();
+        std::mem::forget(edit);
+        assert_eq!(*name, "sui");
+    }
+}
@@ -0,0 +1,605 @@
+// ---------------- Rope 文本缓冲区 ----------------
+/*
+    what: append_world 往 String 末尾追加很便宜，但在大文件中间插入、删除要移动后面所有字节，O(n)。
+          Rope 把文本切成小块（叶子）挂在平衡二叉树上，编辑只改动一条路径，O(log n)。
+    how:
+        - 所有位置都是 char 偏移（不是字节），中文等多字节字符不会被切开;
+        - 每个节点缓存 字符数、字节数、换行数、高度，按 AVL 规则保持平衡;
+        - split(at) 把树拆成两棵，join 把两棵树拼起来（高度差决定递归深度），
+          insert = split + join + join，remove = split + split + join;
+        - 行列与偏移互转: 沿换行数向下查找，行号、列号都从 0 开始，列按 char 计;
+        - chunks(range): 按顺序借出各叶子中的 &str，不复制文本;
+*/
+
+use std::fmt;
+use std::ops::Range;
+
+// 叶子的字节数上限，相邻的小叶子在 join 时会合并
+const MAX_LEAF: usize = 512;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(String),
+    Internal {
+        left: Box<Node>,
+        right: Box<Node>,
+        chars: usize,
+        bytes: usize,
+        newlines: usize,
+        height: usize,
+    },
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::Leaf(String::new())
+    }
+}
+
+fn char_to_byte(s: &str, char_idx: usize) -> usize {
+    s.char_indices().nth(char_idx).map_or(s.len(), |(i, _)| i)
+}
+
+impl Node {
+    fn leaf(text: &str) -> Node {
+        Node::Leaf(text.to_string())
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            Node::Leaf(s) => s.chars().count(),
+            Node::Internal { chars, .. } => *chars,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            Node::Leaf(s) => s.len(),
+            Node::Internal { bytes, .. } => *bytes,
+        }
+    }
+
+    fn newlines(&self) -> usize {
+        match self {
+            Node::Leaf(s) => s.bytes().filter(|&b| b == b'\n').count(),
+            Node::Internal { newlines, .. } => *newlines,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Internal { height, .. } => *height,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes() == 0
+    }
+
+    // 不检查平衡，调用方保证左右高度差不超过 1
+    fn internal(left: Node, right: Node) -> Node {
+        Node::Internal {
+            chars: left.chars() + right.chars(),
+            bytes: left.bytes() + right.bytes(),
+            newlines: left.newlines() + right.newlines(),
+            height: left.height().max(right.height()) + 1,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    // 由整段文本构造平衡的树
+    fn build(text: &str) -> Node {
+        let mut leaves = Vec::new();
+        let mut rest = text;
+        while rest.len() > MAX_LEAF {
+            let mut cut = MAX_LEAF;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let (head, tail) = rest.split_at(cut);
+            leaves.push(Node::leaf(head));
+            rest = tail;
+        }
+        leaves.push(Node::leaf(rest));
+        Self::build_balanced(leaves)
+    }
+
+    fn build_balanced(mut leaves: Vec<Node>) -> Node {
+        if leaves.len() == 1 {
+            return leaves.pop().unwrap_or_default();
+        }
+        let right = leaves.split_off(leaves.len() / 2);
+        Node::internal(Self::build_balanced(leaves), Self::build_balanced(right))
+    }
+
+    fn into_children(self) -> (Node, Node) {
+        match self {
+            Node::Internal { left, right, .. } => (*left, *right),
+            Node::Leaf(_) => unreachable!("叶子没有子节点"),
+        }
+    }
+
+    fn rotate_left(self) -> Node {
+        let (a, right) = self.into_children();
+        let (b, c) = right.into_children();
+        Node::internal(Node::internal(a, b), c)
+    }
+
+    fn rotate_right(self) -> Node {
+        let (left, c) = self.into_children();
+        let (a, b) = left.into_children();
+        Node::internal(a, Node::internal(b, c))
+    }
+
+    // 左右高度差为 2 时旋转
+    fn rebalance(left: Node, right: Node) -> Node {
+        let (lh, rh) = (left.height(), right.height());
+        if lh > rh + 1 {
+            let left = match &left {
+                Node::Internal { left: ll, right: lr, .. } if lr.height() > ll.height() => left.rotate_left(),
+                _ => left,
+            };
+            Node::internal(left, right).rotate_right()
+        } else if rh > lh + 1 {
+            let right = match &right {
+                Node::Internal { left: rl, right: rr, .. } if rl.height() > rr.height() => right.rotate_right(),
+                _ => right,
+            };
+            Node::internal(left, right).rotate_left()
+        } else {
+            Node::internal(left, right)
+        }
+    }
+
+    // 把 right 拼到 left 后面，O(|高度差|)
+    fn join(left: Node, right: Node) -> Node {
+        if left.is_empty() {
+            return right;
+        }
+        if right.is_empty() {
+            return left;
+        }
+        let (lh, rh) = (left.height(), right.height());
+        if lh > rh + 1 {
+            let (a, b) = left.into_children();
+            Self::rebalance(a, Self::join(b, right))
+        } else if rh > lh + 1 {
+            let (a, b) = right.into_children();
+            Self::rebalance(Self::join(left, a), b)
+        } else {
+            match (left, right) {
+                (Node::Leaf(mut a), Node::Leaf(b)) if a.len() + b.len() <= MAX_LEAF => {
+                    a.push_str(&b);
+                    Node::Leaf(a)
+                }
+                (left, right) => Node::internal(left, right),
+            }
+        }
+    }
+
+    // 在第 at 个字符处拆开
+    fn split(self, at: usize) -> (Node, Node) {
+        match self {
+            Node::Leaf(mut s) => {
+                let tail = s.split_off(char_to_byte(&s, at));
+                (Node::Leaf(s), Node::Leaf(tail))
+            }
+            Node::Internal { left, right, .. } => {
+                let left_chars = left.chars();
+                if at <= left_chars {
+                    let (a, b) = left.split(at);
+                    (a, Self::join(b, *right))
+                } else {
+                    let (c, d) = right.split(at - left_chars);
+                    (Self::join(*left, c), d)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RopeError {
+    OutOfBounds { index: usize, len: usize },
+    InvalidRange { start: usize, end: usize },
+    NoSuchLine { line: usize, lines: usize },
+}
+
+impl fmt::Display for RopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RopeError::OutOfBounds { index, len } => write!(f, "字符偏移 {index} 越界，共 {len} 个字符"),
+            RopeError::InvalidRange { start, end } => write!(f, "无效范围: {start}..{end}"),
+            RopeError::NoSuchLine { line, lines } => write!(f, "第 {line} 行不存在，共 {lines} 行"),
+        }
+    }
+}
+
+impl std::error::Error for RopeError {}
+
+#[derive(Debug, Clone)]
+pub struct Rope {
+    root: Node,
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Rope { root: Node::default() }
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.root.chars()
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.bytes()
+    }
+
+    // 空文本也算 1 行
+    pub fn len_lines(&self) -> usize {
+        self.root.newlines() + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), RopeError> {
+        let len = self.len_chars();
+        if index > len {
+            return Err(RopeError::OutOfBounds { index, len });
+        }
+        Ok(())
+    }
+
+    fn check_range(&self, range: &Range<usize>) -> Result<(), RopeError> {
+        if range.start > range.end {
+            return Err(RopeError::InvalidRange { start: range.start, end: range.end });
+        }
+        self.check_index(range.end)
+    }
+
+    // 相当于 append_world 中的 push_str
+    pub fn append(&mut self, text: &str) {
+        let root = std::mem::take(&mut self.root);
+        self.root = Node::join(root, Node::build(text));
+    }
+
+    pub fn insert(&mut self, at: usize, text: &str) -> Result<(), RopeError> {
+        self.check_index(at)?;
+        let (left, right) = std::mem::take(&mut self.root).split(at);
+        self.root = Node::join(Node::join(left, Node::build(text)), right);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, range: Range<usize>) -> Result<(), RopeError> {
+        self.check_range(&range)?;
+        let (rest, tail) = std::mem::take(&mut self.root).split(range.end);
+        let (head, _removed) = rest.split(range.start);
+        self.root = Node::join(head, tail);
+        Ok(())
+    }
+
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        // 先检查越界，index + 1 在 usize::MAX 时才不会溢出
+        if index >= self.len_chars() {
+            return None;
+        }
+        self.chunks(index..index + 1).ok()?.next()?.chars().next()
+    }
+
+    // 按顺序借出 range 范围内的文本块
+    pub fn chunks(&self, range: Range<usize>) -> Result<Chunks<'_>, RopeError> {
+        self.check_range(&range)?;
+        Ok(Chunks { stack: vec![&self.root], skip: range.start, remaining: range.len() })
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        Chunks { stack: vec![&self.root], skip: 0, remaining: self.len_chars() }.flat_map(str::chars)
+    }
+
+    pub fn slice_to_string(&self, range: Range<usize>) -> Result<String, RopeError> {
+        Ok(self.chunks(range)?.collect())
+    }
+
+    // ---------- 行列 ----------
+
+    // 第 line 行行首的字符偏移
+    pub fn line_to_char(&self, line: usize) -> Result<usize, RopeError> {
+        let lines = self.len_lines();
+        if line >= lines {
+            return Err(RopeError::NoSuchLine { line, lines });
+        }
+        if line == 0 {
+            return Ok(0);
+        }
+        // 找第 line 个换行符之后的位置
+        let (mut node, mut remaining, mut offset) = (&self.root, line, 0);
+        loop {
+            match node {
+                Node::Internal { left, right, .. } => {
+                    if left.newlines() >= remaining {
+                        node = left;
+                    } else {
+                        remaining -= left.newlines();
+                        offset += left.chars();
+                        node = right;
+                    }
+                }
+                Node::Leaf(s) => {
+                    let newline = s.chars().enumerate().filter(|&(_, c)| c == '\n').nth(remaining - 1);
+                    return Ok(offset + newline.map_or(0, |(i, _)| i + 1));
+                }
+            }
+        }
+    }
+
+    // (行, 列)
+    pub fn char_to_line_col(&self, index: usize) -> Result<(usize, usize), RopeError> {
+        self.check_index(index)?;
+        let (mut node, mut remaining, mut line) = (&self.root, index, 0);
+        loop {
+            match node {
+                Node::Internal { left, right, .. } => {
+                    if remaining < left.chars() {
+                        node = left;
+                    } else {
+                        remaining -= left.chars();
+                        line += left.newlines();
+                        node = right;
+                    }
+                }
+                Node::Leaf(s) => {
+                    line += s.chars().take(remaining).filter(|&c| c == '\n').count();
+                    break;
+                }
+            }
+        }
+        Ok((line, index - self.line_to_char(line)?))
+    }
+
+    // 第 line 行不含换行符的范围
+    fn line_range(&self, line: usize) -> Result<Range<usize>, RopeError> {
+        let start = self.line_to_char(line)?;
+        let end = self.line_to_char(line + 1).map_or(self.len_chars(), |next| next - 1);
+        Ok(start..end)
+    }
+
+    // 列超过行长时报错
+    pub fn line_col_to_char(&self, line: usize, col: usize) -> Result<usize, RopeError> {
+        let range = self.line_range(line)?;
+        if range.start + col > range.end {
+            return Err(RopeError::OutOfBounds { index: range.start + col, len: range.end });
+        }
+        Ok(range.start + col)
+    }
+
+    pub fn line(&self, line: usize) -> Result<String, RopeError> {
+        self.slice_to_string(self.line_range(line)?)
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Rope { root: Node::build(text) }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.chunks(0..self.len_chars()).map_err(|_| fmt::Error)?.try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Self) -> bool {
+        self.len_bytes() == other.len_bytes() && self.chars().eq(other.chars())
+    }
+}
+
+impl Eq for Rope {}
+
+// 先序遍历叶子，跳过整个落在 range 之前的子树
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+    skip: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while self.remaining > 0 {
+            let node = self.stack.pop()?;
+            if node.chars() <= self.skip {
+                self.skip -= node.chars();
+                continue;
+            }
+            match node {
+                Node::Internal { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+                Node::Leaf(s) => {
+                    let start = char_to_byte(s, self.skip);
+                    let tail = &s[start..];
+                    let end = char_to_byte(tail, self.remaining);
+                    let chunk = &tail[..end];
+                    self.remaining -= chunk.chars().count();
+                    self.skip = 0;
+                    return Some(chunk);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 字符偏移 -> 字节偏移的 String 模型
+    fn byte_at(s: &str, at: usize) -> usize {
+        char_to_byte(s, at)
+    }
+
+    // 检查 AVL 平衡和缓存的统计值，返回高度
+    fn check_node(node: &Node) -> usize {
+        match node {
+            Node::Leaf(s) => {
+                assert!(s.len() <= MAX_LEAF, "叶子 {} 字节", s.len());
+                0
+            }
+            Node::Internal { left, right, chars, bytes, newlines, height } => {
+                let (lh, rh) = (check_node(left), check_node(right));
+                assert!(lh.abs_diff(rh) <= 1, "左右高度 {lh} / {rh} 不平衡");
+                assert_eq!(*height, lh.max(rh) + 1);
+                assert_eq!(*chars, left.chars() + right.chars());
+                assert_eq!(*bytes, left.bytes() + right.bytes());
+                assert_eq!(*newlines, left.newlines() + right.newlines());
+                *height
+            }
+        }
+    }
+
+    // xorshift，测试不依赖 rand
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    #[test]
+    fn insert_and_remove_match_string_with_multibyte_text() {
+        let mut rope = Rope::from("hello");
+        let mut model = String::from("hello");
+        for (at, text) in [(0, "你好，"), (3, "世界"), (5, "🦀\n"), (11, "é"), (2, "")] {
+            rope.insert(at, text).unwrap();
+            model.insert_str(byte_at(&model, at), text);
+            assert_eq!(rope.to_string(), model);
+        }
+        rope.remove(1..4).unwrap();
+        model.replace_range(byte_at(&model, 1)..byte_at(&model, 4), "");
+        assert_eq!(rope.to_string(), model);
+        assert_eq!(rope.len_chars(), model.chars().count());
+        assert_eq!(rope.len_bytes(), model.len());
+        assert_eq!(rope.insert(99, "x"), Err(RopeError::OutOfBounds { index: 99, len: model.chars().count() }));
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 3..1;
+        assert_eq!(rope.remove(reversed), Err(RopeError::InvalidRange { start: 3, end: 1 }));
+    }
+
+    #[test]
+    fn slice_and_char_at_respect_char_boundaries() {
+        let text = "ab中文cd🦀ef";
+        let rope = Rope::from(text);
+        let chars: Vec<char> = text.chars().collect();
+        for start in 0..=chars.len() {
+            for end in start..=chars.len() {
+                let expected: String = chars[start..end].iter().collect();
+                assert_eq!(rope.slice_to_string(start..end).unwrap(), expected);
+            }
+        }
+        assert_eq!(rope.char_at(2), Some('中'));
+        assert_eq!(rope.char_at(6), Some('🦀'));
+        assert_eq!(rope.char_at(chars.len()), None);
+        assert_eq!(rope.char_at(usize::MAX), None);
+    }
+
+    #[test]
+    fn long_text_splits_into_leaves_on_char_boundaries() {
+        // 3 字节的汉字，512 不是 3 的倍数，切分点要回退到字符边界
+        let text = "汉".repeat(1_000);
+        let rope = Rope::from(text.as_str());
+        let chunks: Vec<&str> = rope.chunks(0..rope.len_chars()).unwrap().collect();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= MAX_LEAF));
+        assert_eq!(chunks.concat(), text);
+        check_node(&rope.root);
+    }
+
+    #[test]
+    fn chunks_cover_exactly_the_range() {
+        let mut rope = Rope::new();
+        for i in 0..200 {
+            rope.append(&format!("第{i}行\n"));
+        }
+        let model = rope.to_string();
+        let chars: Vec<char> = model.chars().collect();
+        for range in [0..0, 0..1, 5..900, 100..chars.len(), chars.len()..chars.len()] {
+            let joined: String = rope.chunks(range.clone()).unwrap().collect();
+            assert_eq!(joined, chars[range].iter().collect::<String>());
+        }
+        assert!(rope.chunks(0..chars.len() + 1).is_err());
+    }
+
+    #[test]
+    fn line_and_column_conversions() {
+        let rope = Rope::from("第一行\nsecond\n\n末尾");
+        assert_eq!(rope.len_lines(), 4);
+        assert_eq!(rope.line_to_char(0), Ok(0));
+        assert_eq!(rope.line_to_char(1), Ok(4));
+        assert_eq!(rope.line_to_char(2), Ok(11));
+        assert_eq!(rope.line_to_char(3), Ok(12));
+        assert_eq!(rope.line_to_char(4), Err(RopeError::NoSuchLine { line: 4, lines: 4 }));
+        assert_eq!(rope.char_to_line_col(0), Ok((0, 0)));
+        assert_eq!(rope.char_to_line_col(3), Ok((0, 3)));
+        assert_eq!(rope.char_to_line_col(6), Ok((1, 2)));
+        assert_eq!(rope.char_to_line_col(11), Ok((2, 0)));
+        assert_eq!(rope.char_to_line_col(14), Ok((3, 2)));
+        assert_eq!(rope.line_col_to_char(1, 6), Ok(10));
+        assert!(rope.line_col_to_char(1, 7).is_err());
+        assert_eq!(rope.line(0).unwrap(), "第一行");
+        assert_eq!(rope.line(2).unwrap(), "");
+        assert_eq!(rope.line(3).unwrap(), "末尾");
+        for index in 0..=rope.len_chars() {
+            let (line, col) = rope.char_to_line_col(index).unwrap();
+            assert_eq!(rope.line_col_to_char(line, col), Ok(index));
+        }
+        assert_eq!(Rope::new().len_lines(), 1);
+    }
+
+    #[test]
+    fn random_edits_keep_tree_balanced() {
+        let alphabet = ["a", "bc", "中", "文字", "🦀", "\n", "é", "xyz\n"];
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        let mut rope = Rope::new();
+        let mut model = String::new();
+        for step in 0..1_500 {
+            let len = model.chars().count();
+            if len > 0 && rng.below(3) == 0 {
+                let start = rng.below(len + 1);
+                let end = (start + rng.below(40)).min(len);
+                rope.remove(start..end).unwrap();
+                model.replace_range(byte_at(&model, start)..byte_at(&model, end), "");
+            } else {
+                let at = rng.below(len + 1);
+                let text = alphabet[rng.below(alphabet.len())].repeat(1 + rng.below(60));
+                rope.insert(at, &text).unwrap();
+                model.insert_str(byte_at(&model, at), &text);
+            }
+            if step % 10 == 0 {
+                assert_eq!(rope.to_string(), model);
+                check_node(&rope.root);
+            }
+        }
+        assert_eq!(rope.to_string(), model);
+        assert_eq!(rope.len_lines(), model.matches('\n').count() + 1);
+        assert_eq!(rope, Rope::from(model.as_str()));
+    }
+}
@@ -0,0 +1,171 @@
+// ---------------- 计数分配器 ----------------
+/*
+    what: 包装系统分配器，统计分配次数、字节数和峰值，验证 "避免不必要的 clone"、"引用零成本"。
+    how:
+        - 在二进制或测试的 crate 根部安装:
+              #[global_allocator]
+              static ALLOC: CountingAlloc = CountingAlloc;
+        - measure(|| ...) 返回闭包的结果和 AllocStats，只统计当前线程，其他线程的分配不会干扰;
+          可以嵌套，内层的分配同时计入外层;
+        - assert_no_alloc(|| ...): 热路径上不允许分配，否则 panic;
+        - 没有安装 CountingAlloc 时统计恒为 0，is_installed() 可以检查;
+        - 计数按执行分配/释放的线程归属: A 线程分配、B 线程释放（如把 Vec 发送到别的线程再 drop）时，
+          A 的 net_bytes 偏大，B 的 net_bytes 为负; 要统计跨线程的数据，在同一个线程里分配和释放;
+        - tests/alloc.rs 安装了 CountingAlloc，用 measure / assert_no_alloc 验证这里的结论;
+    why: 计数器放在线程局部变量里，const 初始化，访问时本身不会再分配内存。
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+pub struct CountingAlloc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub deallocations: u64,
+    pub reallocations: u64,
+    pub bytes_allocated: u64,
+    pub bytes_freed: u64,
+    // 闭包执行期间，相对开始时多占用的最大字节数
+    pub peak_bytes: u64,
+}
+
+impl AllocStats {
+    // 执行结束后仍未释放的字节数，负数表示释放了之前（或其他线程）分配的内存
+    pub fn net_bytes(&self) -> i64 {
+        self.bytes_allocated as i64 - self.bytes_freed as i64
+    }
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "分配 {} 次 / {} 字节，释放 {} 次，重新分配 {} 次，峰值 {} 字节",
+            self.allocations, self.bytes_allocated, self.deallocations, self.reallocations, self.peak_bytes
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    deallocations: u64,
+    reallocations: u64,
+    bytes_allocated: u64,
+    bytes_freed: u64,
+    // 本线程分配减去本线程释放，释放其他线程分配的内存时会小于 0
+    live: i64,
+    peak: i64,
+}
+
+const ZERO: Counters = Counters {
+    allocations: 0,
+    deallocations: 0,
+    reallocations: 0,
+    bytes_allocated: 0,
+    bytes_freed: 0,
+    live: 0,
+    peak: 0,
+};
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const { Cell::new(ZERO) };
+}
+
+// 线程退出时线程局部变量可能已销毁，此时不再计数
+fn update(f: impl FnOnce(&mut Counters)) {
+    let _ = COUNTERS.try_with(|cell| {
+        let mut c = cell.get();
+        f(&mut c);
+        c.peak = c.peak.max(c.live);
+        cell.set(c);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            update(|c| {
+                c.allocations += 1;
+                c.bytes_allocated += layout.size() as u64;
+                c.live += layout.size() as i64;
+            });
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            update(|c| {
+                c.allocations += 1;
+                c.bytes_allocated += layout.size() as u64;
+                c.live += layout.size() as i64;
+            });
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        update(|c| {
+            c.deallocations += 1;
+            c.bytes_freed += layout.size() as u64;
+            c.live -= layout.size() as i64;
+        });
+    }
+
+    // 按 "释放旧块 + 分配新块" 计字节
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            update(|c| {
+                c.reallocations += 1;
+                c.bytes_allocated += new_size as u64;
+                c.bytes_freed += layout.size() as u64;
+                c.live += new_size as i64 - layout.size() as i64;
+            });
+        }
+        new_ptr
+    }
+}
+
+fn snapshot() -> Counters {
+    COUNTERS.try_with(Cell::get).unwrap_or(ZERO)
+}
+
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let before = snapshot();
+    // 峰值从当前占用重新开始记，结束后再并回外层
+    update(|c| c.peak = c.live);
+    let result = f();
+    let after = snapshot();
+    update(|c| c.peak = c.peak.max(before.peak));
+    let stats = AllocStats {
+        allocations: after.allocations - before.allocations,
+        deallocations: after.deallocations - before.deallocations,
+        reallocations: after.reallocations - before.reallocations,
+        bytes_allocated: after.bytes_allocated - before.bytes_allocated,
+        bytes_freed: after.bytes_freed - before.bytes_freed,
+        peak_bytes: (after.peak - before.live).max(0) as u64,
+    };
+    (result, stats)
+}
+
+#[track_caller]
+pub fn assert_no_alloc<R>(f: impl FnOnce() -> R) -> R {
+    let (result, stats) = measure(f);
+    if stats.allocations + stats.reallocations > 0 {
+        panic!("不应分配内存，实际: {stats}");
+    }
+    result
+}
+
+pub fn is_installed() -> bool {
+    let (_, stats) = measure(|| std::hint::black_box(Box::new(0u8)));
+    stats.allocations > 0
+}
@@ -0,0 +1,367 @@
+// ---------------- Arena<T> 与 Pool<T> ----------------
+/*
+    what: 所有对象由 Arena 统一拥有，外部只持有 Handle（下标 + 代数），不持有引用。
+    why: 图、树这类互相指向的结构用引用会和借用检查器冲突，用 Handle 则只在访问时借用 Arena。
+    how:
+        - 槽位被 remove 后代数 +1 并放入空闲链表，insert 优先复用空闲槽位;
+        - Handle 记录创建时的代数，代数不一致说明对象已被删除，get 返回 None，
+          不会误指向后来放进同一槽位的新对象;
+        - 代数用尽（u32::MAX）的槽位不再复用;
+        - Pool<T>: 在 Arena 之上保留释放的对象，acquire 时先 reset 再复用，
+          适合 Vec、String 这类带堆内存的对象，避免反复分配;
+*/
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    // 不拥有 T，只用来区分不同 Arena 的 Handle 类型
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(index: u32, generation: u32) -> Self {
+        Handle { index, generation, _marker: PhantomData }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// 手写实现: derive 会要求 T 也实现这些 trait
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Slot<T> {
+    Occupied { generation: u32, value: T },
+    Vacant { generation: u32, next_free: Option<u32> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free_head: Option<u32>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Arena { slots: Vec::new(), free_head: None, len: 0 }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Arena { slots: Vec::with_capacity(capacity), free_head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.len += 1;
+        if let Some(index) = self.free_head {
+            let slot = &mut self.slots[index as usize];
+            let Slot::Vacant { generation, next_free } = *slot else {
+                unreachable!("空闲链表中只有空槽位");
+            };
+            self.free_head = next_free;
+            *slot = Slot::Occupied { generation, value };
+            return Handle::new(index, generation);
+        }
+        let index = u32::try_from(self.slots.len()).expect("Arena 槽位数超过 u32::MAX");
+        self.slots.push(Slot::Occupied { generation: 0, value });
+        Handle::new(index, 0)
+    }
+
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index())?;
+        match slot {
+            Slot::Occupied { generation, .. } if *generation == handle.generation => {}
+            _ => return None,
+        }
+        // 代数用尽的槽位不放回空闲链表
+        let (generation, next_free) = match handle.generation.checked_add(1) {
+            Some(next) => {
+                let head = self.free_head.replace(handle.index);
+                (next, head)
+            }
+            None => (u32::MAX, None),
+        };
+        let old = std::mem::replace(slot, Slot::Vacant { generation, next_free });
+        self.len -= 1;
+        match old {
+            Slot::Occupied { value, .. } => Some(value),
+            Slot::Vacant { .. } => None,
+        }
+    }
+
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        match self.slots.get(handle.index())? {
+            Slot::Occupied { generation, value } if *generation == handle.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        match self.slots.get_mut(handle.index())? {
+            Slot::Occupied { generation, value } if *generation == handle.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    // 同时可变借用两个不同的对象，如给图的两个节点互相加边
+    pub fn get2_mut(&mut self, a: Handle<T>, b: Handle<T>) -> Option<(&mut T, &mut T)> {
+        if a.index == b.index || !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+        let (low, high, swapped) = if a.index < b.index { (a, b, false) } else { (b, a, true) };
+        let (head, tail) = self.slots.split_at_mut(high.index());
+        let (Slot::Occupied { value: x, .. }, Slot::Occupied { value: y, .. }) = (&mut head[low.index()], &mut tail[0])
+        else {
+            return None;
+        };
+        Some(if swapped { (y, x) } else { (x, y) })
+    }
+
+    pub fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            if let Slot::Occupied { generation, .. } = self.slots[index] {
+                self.remove(Handle::new(index as u32, generation));
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.slots.iter().enumerate().filter_map(|(i, slot)| match slot {
+            Slot::Occupied { generation, value } => Some((Handle::new(i as u32, *generation), value)),
+            Slot::Vacant { .. } => None,
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(i, slot)| match slot {
+            Slot::Occupied { generation, value } => Some((Handle::new(i as u32, *generation), value)),
+            Slot::Vacant { .. } => None,
+        })
+    }
+
+    pub fn handles(&self) -> Vec<Handle<T>> {
+        self.iter().map(|(h, _)| h).collect()
+    }
+}
+
+impl<T> std::ops::Index<Handle<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle<T>) -> &T {
+        self.get(handle).unwrap_or_else(|| panic!("{handle:?} 已失效"))
+    }
+}
+
+impl<T> std::ops::IndexMut<Handle<T>> for Arena<T> {
+    fn index_mut(&mut self, handle: Handle<T>) -> &mut T {
+        self.get_mut(handle).unwrap_or_else(|| panic!("{handle:?} 已失效"))
+    }
+}
+
+// ---------- Pool ----------
+
+pub struct Pool<T> {
+    arena: Arena<T>,
+    spare: Vec<T>,
+    max_spare: usize,
+    create: fn() -> T,
+    reset: fn(&mut T),
+}
+
+impl<T> Pool<T> {
+    // Pool::new(|| Vec::with_capacity(64), Vec::clear)
+    pub fn new(create: fn() -> T, reset: fn(&mut T)) -> Self {
+        Pool { arena: Arena::new(), spare: Vec::new(), max_spare: usize::MAX, create, reset }
+    }
+
+    // 最多保留多少个空闲对象，超出的直接 drop
+    pub fn max_spare(mut self, n: usize) -> Self {
+        self.max_spare = n;
+        self.spare.truncate(n);
+        self
+    }
+
+    pub fn acquire(&mut self) -> Handle<T> {
+        let value = self.spare.pop().unwrap_or_else(self.create);
+        self.arena.insert(value)
+    }
+
+    // 失效的 Handle 返回 false
+    pub fn release(&mut self, handle: Handle<T>) -> bool {
+        let Some(mut value) = self.arena.remove(handle) else {
+            return false;
+        };
+        if self.spare.len() < self.max_spare {
+            (self.reset)(&mut value);
+            self.spare.push(value);
+        }
+        true
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.arena.get(handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.arena.get_mut(handle)
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn spare(&self) -> usize {
+        self.spare.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.arena.iter()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool").field("arena", &self.arena).field("spare", &self.spare.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_handle_does_not_see_reused_slot() {
+        let mut arena = Arena::new();
+        let old = arena.insert("sui");
+        assert_eq!(arena.remove(old), Some("sui"));
+        let new = arena.insert("sonic");
+        // 同一个槽位，代数不同
+        assert_eq!(new.index(), old.index());
+        assert_eq!(new.generation(), old.generation() + 1);
+        assert_eq!(arena.get(old), None);
+        assert_eq!(arena.get_mut(old), None);
+        assert_eq!(arena.remove(old), None);
+        assert_eq!(arena.get(new), Some(&"sonic"));
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn pool_recycles_slots_and_objects() {
+        let mut pool: Pool<Vec<u8>> = Pool::new(|| Vec::with_capacity(64), Vec::clear);
+        let first = pool.acquire();
+        pool.get_mut(first).unwrap().extend_from_slice(b"data");
+        assert!(pool.release(first));
+        assert!(!pool.release(first));
+        assert_eq!((pool.in_use(), pool.spare()), (0, 1));
+
+        let second = pool.acquire();
+        assert_eq!(second.index(), first.index());
+        assert_ne!(second, first);
+        let reused = pool.get(second).unwrap();
+        assert!(reused.is_empty());
+        assert!(reused.capacity() >= 64);
+        assert_eq!(pool.get(first), None);
+        assert_eq!((pool.in_use(), pool.spare()), (1, 0));
+    }
+
+    #[test]
+    fn pool_keeps_at_most_max_spare() {
+        let mut pool: Pool<String> = Pool::new(String::new, String::clear).max_spare(1);
+        let handles: Vec<_> = (0..3).map(|_| pool.acquire()).collect();
+        for handle in handles {
+            pool.release(handle);
+        }
+        assert_eq!(pool.spare(), 1);
+    }
+
+    #[test]
+    fn get2_mut_rejects_same_and_stale_handles() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        let (x, y) = arena.get2_mut(b, a).unwrap();
+        std::mem::swap(x, y);
+        assert_eq!((arena[a], arena[b]), (2, 1));
+
+        assert!(arena.get2_mut(a, a).is_none());
+        arena.remove(b);
+        let c = arena.insert(3);
+        assert!(arena.get2_mut(a, b).is_none());
+        assert!(arena.get2_mut(b, a).is_none());
+        assert!(arena.get2_mut(a, c).is_some());
+    }
+
+    #[test]
+    fn exhausted_generation_retires_the_slot() {
+        let mut arena = Arena::new();
+        let handle = arena.insert('a');
+        // 直接把槽位推到最后一代，省去 2^32 次插入删除
+        arena.slots[handle.index()] = Slot::Occupied { generation: u32::MAX, value: 'a' };
+        let last = Handle::new(handle.index, u32::MAX);
+        assert_eq!(arena.remove(last), Some('a'));
+        assert_eq!(arena.free_head, None);
+
+        let next = arena.insert('b');
+        assert_ne!(next.index(), handle.index());
+        assert_eq!(arena.get(last), None);
+        assert_eq!(arena.len(), 1);
+        // clear 也不会把退役的槽位放回空闲链表
+        arena.clear();
+        assert_eq!(arena.insert('c').index(), next.index());
+    }
+}
@@ -0,0 +1,42 @@
+// clone 与借用的分配对比: cargo run --bin alloc_demo
+use four_ownership::alloc::{assert_no_alloc, is_installed, measure, CountingAlloc};
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+// 课程中的两个函数（课程里参数是 &String，效果相同）
+fn takes_ownership(s: String) -> usize {
+    s.len()
+}
+
+fn calculate_length(s: &str) -> usize {
+    s.len()
+}
+
+fn main() {
+    assert!(is_installed(), "CountingAlloc 没有安装");
+    let s = "所有权与借用 ".repeat(1000);
+
+    let (len, stats) = measure(|| takes_ownership(s.clone()));
+    println!("takes_ownership(s.clone()) = {len}: {stats}");
+
+    let (len, stats) = measure(|| calculate_length(&s));
+    println!("calculate_length(&s)       = {len}: {stats}");
+
+    // 逐个 push 会多次扩容; 预留容量只分配一次
+    let (_, stats) = measure(|| (0..1000).fold(String::new(), |mut acc, _| {
+        acc.push('x');
+        acc
+    }));
+    println!("String::new + push x1000   : {stats}");
+    let (_, stats) = measure(|| {
+        let mut acc = String::with_capacity(1000);
+        (0..1000).for_each(|_| acc.push('x'));
+        acc
+    });
+    println!("with_capacity + push x1000 : {stats}");
+
+    // 热路径: 借用不分配
+    let total = assert_no_alloc(|| s.split(' ').map(str::len).sum::<usize>());
+    println!("按空格切分求长度（无分配）: {total}");
+}
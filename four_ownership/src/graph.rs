@@ -0,0 +1,377 @@
+// ---------------- Rc / Weak 对象图 ----------------
+/*
+    what: 单一所有权之外的共享所有权: Rc<RefCell<Node>> 表示节点，
+          强引用（Rc）表示拥有，弱引用（Weak）表示回指，如子节点指向父节点。
+    why: 强引用成环时引用计数永远不会归零，整个环都不会被释放（内存泄漏），
+         编译器不会报错，只能在运行时检查。
+    how:
+        - Node::new / add_child（父强子弱）/ link（任意强边）/ link_weak;
+        - GraphReport::scan(roots): 从根出发沿强、弱边遍历，记录每个节点的强、弱引用计数;
+          只看强边求强连通分量（迭代版 Tarjan，长链不会爆栈），节点数 > 1 或有自环的分量就是会泄漏的环;
+        - 强连通分量是一组互相可达的节点，不一定是一条简单环（A⇄B、B⇄C 中没有 C→A 的边），
+          所以按集合输出，再用 cycle_path 在分量内找一条真实存在的最短环作为例子;
+        - to_dot(): 导出 Graphviz，强边实线、弱边虚线，环上的节点标红:
+              dot -Tsvg graph.dot -o graph.svg
+*/
+
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+pub type NodeRef = Rc<RefCell<Node>>;
+
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    pub strong: Vec<NodeRef>,
+    pub weak: Vec<Weak<RefCell<Node>>>,
+}
+
+impl Node {
+    pub fn new(name: impl Into<String>) -> NodeRef {
+        Rc::new(RefCell::new(Node { name: name.into(), strong: Vec::new(), weak: Vec::new() }))
+    }
+}
+
+// 父节点拥有子节点，子节点用 Weak 指回父节点，不会成环
+pub fn add_child(parent: &NodeRef, child: &NodeRef) {
+    parent.borrow_mut().strong.push(Rc::clone(child));
+    child.borrow_mut().weak.push(Rc::downgrade(parent));
+}
+
+pub fn link(from: &NodeRef, to: &NodeRef) {
+    from.borrow_mut().strong.push(Rc::clone(to));
+}
+
+pub fn link_weak(from: &NodeRef, to: &NodeRef) {
+    from.borrow_mut().weak.push(Rc::downgrade(to));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Strong,
+    Weak,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo {
+    pub id: usize,
+    pub name: String,
+    pub strong_count: usize,
+    pub weak_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphReport {
+    pub nodes: Vec<NodeInfo>,
+    pub edges: Vec<(usize, usize, EdgeKind)>,
+    // 每个会泄漏的强连通分量，节点 id 升序; 不是环上的顺序
+    pub cycles: Vec<Vec<usize>>,
+}
+
+impl GraphReport {
+    pub fn scan(roots: &[NodeRef]) -> GraphReport {
+        // 遍历期间自己持有的 Rc 会让强引用计数 +1，统计时扣除
+        let mut visited: Vec<NodeRef> = Vec::new();
+        let mut ids: HashMap<*const RefCell<Node>, usize> = HashMap::new();
+        let mut stack: Vec<NodeRef> = roots.iter().rev().cloned().collect();
+        while let Some(node) = stack.pop() {
+            if ids.contains_key(&Rc::as_ptr(&node)) {
+                continue;
+            }
+            ids.insert(Rc::as_ptr(&node), visited.len());
+            {
+                let n = node.borrow();
+                let weak_targets = n.weak.iter().filter_map(Weak::upgrade);
+                let mut next: Vec<NodeRef> = n.strong.iter().cloned().chain(weak_targets).collect();
+                next.reverse();
+                stack.extend(next);
+            }
+            visited.push(node);
+        }
+        drop(stack);
+
+        let mut edges = Vec::new();
+        for (from, node) in visited.iter().enumerate() {
+            let n = node.borrow();
+            for target in &n.strong {
+                edges.push((from, ids[&Rc::as_ptr(target)], EdgeKind::Strong));
+            }
+            for target in n.weak.iter().filter_map(Weak::upgrade) {
+                edges.push((from, ids[&Rc::as_ptr(&target)], EdgeKind::Weak));
+            }
+        }
+
+        let nodes = visited
+            .iter()
+            .enumerate()
+            .map(|(id, node)| NodeInfo {
+                id,
+                name: node.borrow().name.clone(),
+                strong_count: Rc::strong_count(node) - 1,
+                weak_count: Rc::weak_count(node),
+            })
+            .collect::<Vec<_>>();
+        let cycles = strong_cycles(nodes.len(), &edges);
+        GraphReport { nodes, edges, cycles }
+    }
+
+    pub fn has_cycles(&self) -> bool {
+        !self.cycles.is_empty()
+    }
+
+    pub fn cycle_names(&self) -> Vec<Vec<String>> {
+        self.cycles
+            .iter()
+            .map(|cycle| cycle.iter().map(|&id| self.nodes[id].name.clone()).collect())
+            .collect()
+    }
+
+    // 第 index 个分量中经过其最小 id 节点的最短环: [a, b, c] 表示 a -> b -> c -> a
+    pub fn cycle_path(&self, index: usize) -> Vec<usize> {
+        let component = &self.cycles[index];
+        let start = component[0];
+        let inside = |id: usize| component.binary_search(&id).is_ok();
+        let mut adjacency: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &(from, to, kind) in &self.edges {
+            if kind == EdgeKind::Strong && inside(from) && inside(to) {
+                adjacency.entry(from).or_default().push(to);
+            }
+        }
+        // 在分量内广度优先搜索，回到 start 即得到最短环
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(v) = queue.pop_front() {
+            for &to in adjacency.get(&v).into_iter().flatten() {
+                if to == start {
+                    let mut path = vec![v];
+                    while let Some(&p) = parent.get(path.last().expect("path 非空")) {
+                        path.push(p);
+                    }
+                    path.reverse();
+                    return path;
+                }
+                if let Entry::Vacant(entry) = parent.entry(to) {
+                    entry.insert(v);
+                    queue.push_back(to);
+                }
+            }
+        }
+        unreachable!("强连通分量中的节点一定能回到起点")
+    }
+
+    pub fn to_dot(&self) -> String {
+        // 节点所在环的序号
+        let mut cycle_of: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        for (index, cycle) in self.cycles.iter().enumerate() {
+            for &id in cycle {
+                cycle_of[id] = Some(index);
+            }
+        }
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph G {\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let color = if cycle_of[node.id].is_some() { ", color=red, fontcolor=red" } else { "" };
+            out.push_str(&format!(
+                "    n{} [label=\"{}\\nstrong={} weak={}\"{color}];\n",
+                node.id,
+                escape(&node.name),
+                node.strong_count,
+                node.weak_count
+            ));
+        }
+        for &(from, to, kind) in &self.edges {
+            let style = match kind {
+                EdgeKind::Strong if cycle_of[from].is_some() && cycle_of[from] == cycle_of[to] => " [color=red]",
+                EdgeKind::Strong => "",
+                EdgeKind::Weak => " [style=dashed]",
+            };
+            out.push_str(&format!("    n{from} -> n{to}{style};\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl fmt::Display for GraphReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in &self.nodes {
+            writeln!(f, "{:<16} strong={} weak={}", node.name, node.strong_count, node.weak_count)?;
+        }
+        if self.cycles.is_empty() {
+            return writeln!(f, "没有强引用环");
+        }
+        for (index, names) in self.cycle_names().into_iter().enumerate() {
+            let path: Vec<&str> = self.cycle_path(index).iter().map(|&id| self.nodes[id].name.as_str()).collect();
+            writeln!(f, "强引用环（会泄漏）: {{{}}}，例如 {} -> {}", names.join(", "), path.join(" -> "), path[0])?;
+        }
+        Ok(())
+    }
+}
+
+// Tarjan 强连通分量，只考虑强边; 用显式栈代替递归，(节点, 下一条出边的下标)
+fn strong_cycles(count: usize, edges: &[(usize, usize, EdgeKind)]) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); count];
+    for &(from, to, kind) in edges {
+        if kind == EdgeKind::Strong {
+            adjacency[from].push(to);
+        }
+    }
+
+    let mut index: Vec<Option<usize>> = vec![None; count];
+    let mut low = vec![0; count];
+    let mut on_stack = vec![false; count];
+    let mut stack = Vec::new();
+    let mut next = 0;
+    let mut components = Vec::new();
+
+    for root in 0..count {
+        if index[root].is_some() {
+            continue;
+        }
+        let mut calls: Vec<(usize, usize)> = vec![(root, 0)];
+        while let Some(&mut (v, ref mut edge)) = calls.last_mut() {
+            if *edge == 0 && index[v].is_none() {
+                index[v] = Some(next);
+                low[v] = next;
+                next += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = adjacency[v].get(*edge) {
+                *edge += 1;
+                match index[w] {
+                    None => calls.push((w, 0)),
+                    Some(w_index) if on_stack[w] => low[v] = low[v].min(w_index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            // v 的出边都处理完，相当于递归返回
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    let mut cycles: Vec<Vec<usize>> =
+        components.into_iter().filter(|c| c.len() > 1 || adjacency[c[0]].contains(&c[0])).collect();
+    cycles.sort();
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试结束前拆掉强边，环才会被释放
+    fn break_cycles(nodes: &[NodeRef]) {
+        for node in nodes {
+            node.borrow_mut().strong.clear();
+        }
+    }
+
+    #[test]
+    fn tree_with_weak_parent_links_has_no_cycles() {
+        let root = Node::new("root");
+        let a = Node::new("a");
+        let b = Node::new("b");
+        add_child(&root, &a);
+        add_child(&root, &b);
+
+        let report = GraphReport::scan(std::slice::from_ref(&root));
+        assert!(!report.has_cycles());
+        let counts: Vec<(&str, usize, usize)> =
+            report.nodes.iter().map(|n| (n.name.as_str(), n.strong_count, n.weak_count)).collect();
+        assert_eq!(counts, [("root", 1, 2), ("a", 2, 0), ("b", 2, 0)]);
+        assert_eq!(report.edges.iter().filter(|e| e.2 == EdgeKind::Weak).count(), 2);
+        assert!(report.to_string().contains("没有强引用环"));
+    }
+
+    #[test]
+    fn component_is_reported_as_a_set_with_a_real_cycle() {
+        let nodes: Vec<NodeRef> = ["A", "B", "C"].into_iter().map(Node::new).collect();
+        let (a, b, c) = (&nodes[0], &nodes[1], &nodes[2]);
+        link(a, b);
+        link(b, a);
+        link(b, c);
+        link(c, b);
+
+        let report = GraphReport::scan(std::slice::from_ref(a));
+        assert_eq!(report.cycle_names(), [["A", "B", "C"]]);
+        assert_eq!(report.cycle_path(0), [0, 1]);
+        let text = report.to_string();
+        assert!(text.contains("{A, B, C}，例如 A -> B -> A"), "{text}");
+        assert!(!text.contains("C -> A"));
+        break_cycles(&nodes);
+    }
+
+    #[test]
+    fn self_loop_and_separate_cycles() {
+        let nodes: Vec<NodeRef> = ["x", "y", "z", "w"].into_iter().map(Node::new).collect();
+        link(&nodes[0], &nodes[0]);
+        link(&nodes[0], &nodes[1]);
+        link(&nodes[1], &nodes[2]);
+        link(&nodes[2], &nodes[3]);
+        link(&nodes[3], &nodes[1]);
+        link_weak(&nodes[1], &nodes[0]);
+
+        let report = GraphReport::scan(std::slice::from_ref(&nodes[0]));
+        assert_eq!(report.cycle_names(), [vec!["x"], vec!["y", "z", "w"]]);
+        assert_eq!(report.cycle_path(0), [0]);
+        assert_eq!(report.cycle_path(1), [1, 2, 3]);
+        break_cycles(&nodes);
+    }
+
+    #[test]
+    fn dot_marks_cycle_edges() {
+        let root = Node::new("root \"r\"");
+        let a = Node::new("a");
+        let b = Node::new("b");
+        add_child(&root, &a);
+        link(&a, &b);
+        link(&b, &a);
+
+        let dot = GraphReport::scan(std::slice::from_ref(&root)).to_dot();
+        assert!(dot.starts_with("digraph G {\n"));
+        assert!(dot.contains(r#"n0 [label="root \"r\"\nstrong=1 weak=1"];"#), "{dot}");
+        // a 的强引用: root 的子节点、b 的强边、测试里的变量 a
+        assert!(dot.contains("n1 [label=\"a\\nstrong=3 weak=0\", color=red, fontcolor=red];"), "{dot}");
+        assert!(dot.contains("    n0 -> n1;\n"));
+        assert!(dot.contains("    n1 -> n0 [style=dashed];\n"));
+        assert!(dot.contains("    n1 -> n2 [color=red];\n"));
+        assert!(dot.contains("    n2 -> n1 [color=red];\n"));
+        break_cycles(&[a, b]);
+    }
+
+    #[test]
+    fn long_strong_chain_does_not_overflow() {
+        const LEN: usize = 200_000;
+        let nodes: Vec<NodeRef> = (0..LEN).map(|i| Node::new(i.to_string())).collect();
+        for pair in nodes.windows(2) {
+            link(&pair[0], &pair[1]);
+        }
+        link(&nodes[LEN - 1], &nodes[0]);
+
+        let report = GraphReport::scan(std::slice::from_ref(&nodes[0]));
+        assert_eq!(report.cycles.len(), 1);
+        assert_eq!(report.cycles[0].len(), LEN);
+        assert_eq!(report.cycle_path(0).len(), LEN);
+        break_cycles(&nodes);
+    }
+}
@@ -0,0 +1,162 @@
+// ---------------- ScopeGuard / defer ----------------
+/*
+    what: 基于 Drop 的延迟清理，离开作用域时执行一个闭包。
+    how:
+        - guard(value, f): 总是执行;
+        - guard_on_success(value, f): 正常离开时执行，panic 展开时不执行;
+        - guard_on_unwind(value, f): 只在 panic 展开时执行;
+          二者都用 std::thread::panicking() 判断当前是否在展开;
+        - ScopeGuard::dismiss(g): 取消清理，取回内部的值;
+        - defer! { ... }: 不需要值时的简写;
+    例: 写临时文件，提交（rename）之前出错就删除:
+        let tmp = guard(tmp_path, |p| { let _ = fs::remove_file(p); });
+        fs::write(&*tmp, data)?;           // 出错提前返回，tmp 被删除
+        fs::rename(ScopeGuard::dismiss(tmp), path)?;  // 提交后不再删除
+*/
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::thread;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Always,
+    OnSuccess,
+    OnUnwind,
+}
+
+impl When {
+    fn should_run(self) -> bool {
+        match self {
+            When::Always => true,
+            When::OnSuccess => !thread::panicking(),
+            When::OnUnwind => thread::panicking(),
+        }
+    }
+}
+
+pub struct ScopeGuard<T, F: FnOnce(T)> {
+    // None 表示已经 dismiss
+    inner: Option<(T, F)>,
+    when: When,
+}
+
+pub fn guard<T, F: FnOnce(T)>(value: T, f: F) -> ScopeGuard<T, F> {
+    ScopeGuard { inner: Some((value, f)), when: When::Always }
+}
+
+pub fn guard_on_success<T, F: FnOnce(T)>(value: T, f: F) -> ScopeGuard<T, F> {
+    ScopeGuard { inner: Some((value, f)), when: When::OnSuccess }
+}
+
+pub fn guard_on_unwind<T, F: FnOnce(T)>(value: T, f: F) -> ScopeGuard<T, F> {
+    ScopeGuard { inner: Some((value, f)), when: When::OnUnwind }
+}
+
+impl<T, F: FnOnce(T)> ScopeGuard<T, F> {
+    // 关联函数而不是方法，避免和 Deref 到的 T 的方法重名
+    pub fn dismiss(mut guard: Self) -> T {
+        let (value, _) = guard.inner.take().expect("只在 dismiss 或 drop 中取出");
+        value
+    }
+
+    pub fn when(guard: &Self) -> When {
+        guard.when
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for ScopeGuard<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner.as_ref().expect("只在 dismiss 或 drop 中取出").0
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for ScopeGuard<T, F> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner.as_mut().expect("只在 dismiss 或 drop 中取出").0
+    }
+}
+
+impl<T: fmt::Debug, F: FnOnce(T)> fmt::Debug for ScopeGuard<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScopeGuard").field("value", &**self).field("when", &self.when).finish()
+    }
+}
+
+impl<T, F: FnOnce(T)> Drop for ScopeGuard<T, F> {
+    fn drop(&mut self) {
+        if let Some((value, f)) = self.inner.take()
+            && self.when.should_run()
+        {
+            f(value);
+        }
+    }
+}
+
+// defer! { println!("离开作用域"); }，多个 defer! 按声明的逆序执行
+#[macro_export]
+macro_rules! defer {
+    ($($body:tt)*) => {
+        let _guard = $crate::guard::guard((), |()| { $($body)* });
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn on_success_runs_only_without_panic() {
+        let log = RefCell::new(Vec::new());
+        {
+            let _g = guard_on_success(1, |v| log.borrow_mut().push(v));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _g = guard_on_success(2, |v| log.borrow_mut().push(v));
+            panic!("中途出错");
+        }));
+        assert!(result.is_err());
+        assert_eq!(*log.borrow(), [1]);
+    }
+
+    #[test]
+    fn on_unwind_runs_only_during_panic() {
+        let log = RefCell::new(Vec::new());
+        {
+            let _g = guard_on_unwind(1, |v| log.borrow_mut().push(v));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _always = guard(3, |v| log.borrow_mut().push(v));
+            let _g = guard_on_unwind(2, |v| log.borrow_mut().push(v));
+            panic!("中途出错");
+        }));
+        assert!(result.is_err());
+        // 逆序 drop: 先 OnUnwind，再 Always
+        assert_eq!(*log.borrow(), [2, 3]);
+    }
+
+    #[test]
+    fn dismiss_returns_value_without_running() {
+        let log = RefCell::new(Vec::new());
+        let mut g = guard(String::from("tmp"), |s| log.borrow_mut().push(s));
+        g.push_str(".txt");
+        assert_eq!(ScopeGuard::when(&g), When::Always);
+        assert_eq!(ScopeGuard::dismiss(g), "tmp.txt");
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn defer_runs_in_reverse_order() {
+        let log = RefCell::new(Vec::new());
+        {
+            crate::defer! { log.borrow_mut().push("first"); }
+            crate::defer! { log.borrow_mut().push("second"); }
+            log.borrow_mut().push("body");
+        }
+        assert_eq!(*log.borrow(), ["body", "second", "first"]);
+    }
+}
@@ -0,0 +1,343 @@
+// ---------------- 事务账本 ----------------
+/*
+    what: 用所有权表达记账: 钱只能移动，不能复制。
+    how:
+        - Funds: 一笔钱，没有实现 Clone / Copy;
+          split 从中拿出一部分（不够时报错，所以余额不会为负），merge 消耗另一笔并入,
+          溢出时把另一笔原样还回去，钱不会凭空消失;
+        - Ledger: 账户余额都是 Funds，deposit / withdraw 是钱进出账本的唯一途径，
+          账本记录流入总额 issued，任何时刻所有余额之和都应等于它;
+        - Transaction: 可变借用 Ledger（事务期间不能直接操作账本），
+          transfer 立即移动资金并写入撤销日志，之后的转账能看到新的余额;
+          commit(self) 检查不变量后生效; 没有 commit 就被 drop（提前返回、? 、panic）时
+          按逆序撤销，账本回到事务开始前的状态;
+          drop 中不能返回错误也不应 panic，撤销失败的转账记入 Ledger::unreverted;
+*/
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+// 金额，单位为分
+#[derive(Debug, PartialEq, Eq)]
+pub struct Funds(u64);
+
+impl Funds {
+    // 账本外部的钱，如现金存入
+    pub fn new(cents: u64) -> Self {
+        Funds(cents)
+    }
+
+    pub fn cents(&self) -> u64 {
+        self.0
+    }
+
+    pub fn split(&mut self, cents: u64) -> Option<Funds> {
+        self.0 = self.0.checked_sub(cents)?;
+        Some(Funds(cents))
+    }
+
+    pub fn merge(&mut self, other: Funds) -> Result<(), Funds> {
+        match self.0.checked_add(other.0) {
+            Some(sum) => {
+                self.0 = sum;
+                Ok(())
+            }
+            None => Err(other),
+        }
+    }
+}
+
+impl fmt::Display for Funds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    UnknownAccount(String),
+    DuplicateAccount(String),
+    InsufficientFunds { account: String, balance: u64, requested: u64 },
+    SameAccount(String),
+    ZeroAmount,
+    Overflow { account: String, cents: u64 },
+    Imbalance { issued: u64, total: u64 },
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LedgerError::UnknownAccount(name) => write!(f, "账户不存在: {name}"),
+            LedgerError::DuplicateAccount(name) => write!(f, "账户已存在: {name}"),
+            LedgerError::InsufficientFunds { account, balance, requested } => {
+                write!(f, "{account} 余额不足: 余额 {balance} 分，需要 {requested} 分")
+            }
+            LedgerError::SameAccount(name) => write!(f, "不能转给自己: {name}"),
+            LedgerError::ZeroAmount => write!(f, "金额必须大于 0"),
+            LedgerError::Overflow { account, cents } => write!(f, "{account} 存入 {cents} 分后金额溢出"),
+            LedgerError::Imbalance { issued, total } => write!(f, "账不平: 流入 {issued} 分，余额合计 {total} 分"),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: String,
+    pub to: String,
+    pub cents: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Ledger {
+    accounts: BTreeMap<String, Funds>,
+    issued: u64,
+    history: Vec<Transfer>,
+    // 回滚时没能撤销的转账
+    unreverted: Vec<Transfer>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, name: &str) -> Result<(), LedgerError> {
+        if self.accounts.contains_key(name) {
+            return Err(LedgerError::DuplicateAccount(name.to_string()));
+        }
+        self.accounts.insert(name.to_string(), Funds(0));
+        Ok(())
+    }
+
+    pub fn balance(&self, name: &str) -> Result<u64, LedgerError> {
+        self.accounts.get(name).map(Funds::cents).ok_or_else(|| LedgerError::UnknownAccount(name.to_string()))
+    }
+
+    pub fn total(&self) -> u64 {
+        self.accounts.values().map(Funds::cents).sum()
+    }
+
+    // 已提交的转账
+    pub fn history(&self) -> &[Transfer] {
+        &self.history
+    }
+
+    // 正常情况下总是空的; 非空说明某次回滚没有完全恢复账本
+    pub fn unreverted(&self) -> &[Transfer] {
+        &self.unreverted
+    }
+
+    fn account_mut(&mut self, name: &str) -> Result<&mut Funds, LedgerError> {
+        self.accounts.get_mut(name).ok_or_else(|| LedgerError::UnknownAccount(name.to_string()))
+    }
+
+    // 存入后 funds 归账本所有
+    pub fn deposit(&mut self, name: &str, funds: Funds) -> Result<(), LedgerError> {
+        let cents = funds.cents();
+        let overflow = || LedgerError::Overflow { account: name.to_string(), cents };
+        let issued = self.issued.checked_add(cents).ok_or_else(overflow)?;
+        self.account_mut(name)?.merge(funds).map_err(|_| overflow())?;
+        self.issued = issued;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, name: &str, cents: u64) -> Result<Funds, LedgerError> {
+        // 账本被破坏时余额之和可能多于流入总额，取款会让 issued 变负，此时不动账户;
+        // 账户不存在、余额不足的错误优先报告
+        let Some(issued) = self.issued.checked_sub(cents) else {
+            let balance = self.balance(name)?;
+            if balance < cents {
+                return Err(LedgerError::InsufficientFunds { account: name.to_string(), balance, requested: cents });
+            }
+            return Err(LedgerError::Imbalance { issued: self.issued, total: self.total() });
+        };
+        let funds = self.take(name, cents)?;
+        self.issued = issued;
+        Ok(funds)
+    }
+
+    fn take(&mut self, name: &str, cents: u64) -> Result<Funds, LedgerError> {
+        let account = self.account_mut(name)?;
+        let balance = account.cents();
+        account.split(cents).ok_or_else(|| LedgerError::InsufficientFunds {
+            account: name.to_string(),
+            balance,
+            requested: cents,
+        })
+    }
+
+    fn move_funds(&mut self, from: &str, to: &str, cents: u64) -> Result<(), LedgerError> {
+        // 先确认收款账户存在且放得下，避免钱取出后无处可放
+        let overflow = || LedgerError::Overflow { account: to.to_string(), cents };
+        self.account_mut(to)?.cents().checked_add(cents).ok_or_else(overflow)?;
+        let funds = self.take(from, cents)?;
+        self.account_mut(to)?.merge(funds).map_err(|_| overflow())
+    }
+
+    pub fn check_invariants(&self) -> Result<(), LedgerError> {
+        let total = self.total();
+        if total != self.issued {
+            return Err(LedgerError::Imbalance { issued: self.issued, total });
+        }
+        Ok(())
+    }
+
+    pub fn begin(&mut self) -> Transaction<'_> {
+        Transaction { ledger: self, journal: Vec::new(), committed: false }
+    }
+}
+
+pub struct Transaction<'a> {
+    ledger: &'a mut Ledger,
+    journal: Vec<Transfer>,
+    committed: bool,
+}
+
+impl Transaction<'_> {
+    pub fn transfer(&mut self, from: &str, to: &str, cents: u64) -> Result<(), LedgerError> {
+        if cents == 0 {
+            return Err(LedgerError::ZeroAmount);
+        }
+        if from == to {
+            return Err(LedgerError::SameAccount(from.to_string()));
+        }
+        self.ledger.move_funds(from, to, cents)?;
+        self.journal.push(Transfer { from: from.to_string(), to: to.to_string(), cents });
+        Ok(())
+    }
+
+    // 包含本事务中尚未提交的转账
+    pub fn balance(&self, name: &str) -> Result<u64, LedgerError> {
+        self.ledger.balance(name)
+    }
+
+    pub fn staged(&self) -> &[Transfer] {
+        &self.journal
+    }
+
+    // 不变量不成立时事务随 self 一起被 drop，自动回滚
+    pub fn commit(mut self) -> Result<usize, LedgerError> {
+        self.ledger.check_invariants()?;
+        let count = self.journal.len();
+        self.ledger.history.append(&mut self.journal);
+        self.committed = true;
+        Ok(count)
+    }
+
+    // 显式回滚，效果与直接 drop 相同
+    pub fn rollback(self) {}
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        // 逆序撤销: 每一步撤销时，收款账户一定还持有这笔钱;
+        // 万一失败（只可能是账本已被破坏）也不 panic，记下来继续撤销其余的
+        while let Some(t) = self.journal.pop() {
+            if self.ledger.move_funds(&t.to, &t.from, t.cents).is_err() {
+                self.ledger.unreverted.push(t);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger() -> Ledger {
+        let mut ledger = Ledger::new();
+        ledger.open("alice").unwrap();
+        ledger.open("bob").unwrap();
+        ledger.deposit("alice", Funds::new(100)).unwrap();
+        ledger
+    }
+
+    #[test]
+    fn merge_overflow_gives_the_funds_back() {
+        let mut funds = Funds::new(u64::MAX);
+        let rejected = funds.merge(Funds::new(1)).unwrap_err();
+        assert_eq!(rejected.cents(), 1);
+        assert_eq!(funds.cents(), u64::MAX);
+    }
+
+    #[test]
+    fn deposit_overflow_leaves_ledger_unchanged() {
+        let mut ledger = ledger();
+        let err = ledger.deposit("bob", Funds::new(u64::MAX)).unwrap_err();
+        assert_eq!(err, LedgerError::Overflow { account: "bob".to_string(), cents: u64::MAX });
+        assert_eq!(ledger.balance("bob"), Ok(0));
+        assert_eq!(ledger.total(), 100);
+        assert_eq!(ledger.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn dropped_transaction_rolls_back() {
+        let mut ledger = ledger();
+        {
+            let mut tx = ledger.begin();
+            tx.transfer("alice", "bob", 60).unwrap();
+            tx.transfer("bob", "alice", 10).unwrap();
+            assert_eq!(tx.balance("bob"), Ok(50));
+            assert!(tx.transfer("alice", "bob", 1_000).is_err());
+        }
+        assert_eq!(ledger.balance("alice"), Ok(100));
+        assert_eq!(ledger.balance("bob"), Ok(0));
+        assert!(ledger.history().is_empty());
+        assert!(ledger.unreverted().is_empty());
+    }
+
+    #[test]
+    fn failed_undo_is_recorded_instead_of_panicking() {
+        let mut ledger = ledger();
+        {
+            let mut tx = ledger.begin();
+            tx.transfer("alice", "bob", 60).unwrap();
+            // 绕过 Funds 破坏账本: bob 的钱凭空消失，撤销时余额不足
+            tx.ledger.accounts.insert("bob".to_string(), Funds::new(0));
+        }
+        assert_eq!(ledger.unreverted(), [Transfer { from: "alice".to_string(), to: "bob".to_string(), cents: 60 }]);
+        assert_eq!(ledger.balance("alice"), Ok(40));
+        assert!(ledger.check_invariants().is_err());
+    }
+
+    #[test]
+    fn commit_keeps_transfers() {
+        let mut ledger = ledger();
+        let mut tx = ledger.begin();
+        tx.transfer("alice", "bob", 30).unwrap();
+        assert_eq!(tx.commit(), Ok(1));
+        assert_eq!(ledger.balance("bob"), Ok(30));
+        assert_eq!(ledger.history().len(), 1);
+        assert_eq!(ledger.withdraw("bob", 30).map(|f| f.cents()), Ok(30));
+        assert_eq!(ledger.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn withdraw_reports_errors_without_moving_money() {
+        let mut ledger = ledger();
+        assert_eq!(
+            ledger.withdraw("alice", 101).map(|f| f.cents()),
+            Err(LedgerError::InsufficientFunds { account: "alice".to_string(), balance: 100, requested: 101 })
+        );
+        assert_eq!(ledger.withdraw("carol", 1).map(|f| f.cents()), Err(LedgerError::UnknownAccount("carol".to_string())));
+        assert_eq!(ledger.balance("alice"), Ok(100));
+        assert_eq!(ledger.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn withdraw_from_corrupted_ledger_does_not_underflow() {
+        let mut ledger = ledger();
+        // 绕过 deposit 凭空多出的钱，issued 没有计入
+        ledger.accounts.insert("bob".to_string(), Funds::new(500));
+        assert_eq!(
+            ledger.withdraw("bob", 300).map(|f| f.cents()),
+            Err(LedgerError::Imbalance { issued: 100, total: 600 })
+        );
+        assert_eq!(ledger.balance("bob"), Ok(500));
+    }
+}
@@ -0,0 +1,9 @@
+// ---------------- four_ownership 库 ----------------
+// 所有权课程的延伸，main.rs 继续保留课程示例
+
+pub mod alloc;
+pub mod arena;
+pub mod graph;
+pub mod guard;
+pub mod ledger;
+pub mod tracked;
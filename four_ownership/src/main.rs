@@ -122,14 +122,10 @@
 // 5、切片 Slices
 // 切片是借用的一部分数据，字符串切片 &str;
 
+// 只找 b' ' 的话，制表符、全角空格、标点都不算分隔，中文整句会被当成一个单词;
+// 这里改用 six_slices::words 按 Unicode 字符类别切分
 // fn first_word(s: &str) -> &str { //接收 &str String的借用
-//     let bytes = s.as_bytes();
-//     for (i, &item) in bytes.iter().enumerate(){
-//         if item == b' ' {
-//             return &s[0..i]
-//         }
-//     }
-//     &s[..]
+//     six_slices::words::first_word(s)
 // }
 // fn main(){
 //     let s = String::from("solana sui!");
@@ -166,6 +162,9 @@ Drop trait：
 RAII（Resource Acquisition Is Initialization）：
     资源在创建时获取，销毁时释放。
 */ 
+use four_ownership::defer;
+use four_ownership::tracked::Registry;
+
 struct Custom{
     data: String,
 }
@@ -179,7 +178,23 @@ impl Drop for Custom{
 fn main(){
     let c = Custom{data: String::from("solana!")};
     println!("waiting...");
-    
+
+    // Tracked<T>: 把 drop 记录下来，检查顺序和泄漏; 变量按声明的逆序 drop
+    let registry = Registry::new();
+    {
+        let _file = registry.track("file", Custom{data: String::from("file")});
+        let _lock = registry.track("lock", Custom{data: String::from("lock")});
+    }
+    registry.assert_drop_order(&["lock", "file"]);
+    registry.assert_no_leaks();
+    print!("{}", registry.timeline());
+
+    // defer!: 离开作用域时执行，多个按逆序执行
+    {
+        defer! { println!("清理 2"); }
+        defer! { println!("清理 1"); }
+        println!("工作中...");
+    }
 } // drop会自动执行，并输入dropping的内容
 
 
@@ -0,0 +1,268 @@
+// ---------------- Tracked<T>: 记录创建与 drop ----------------
+/*
+    what: 课程中 Custom 的 Drop 只打印 "Dropping: ..."，这里把它变成可以检查的记录。
+    how:
+        - Registry: 记录每个对象的创建、drop 事件（序号、相对时间、标签）;
+          Registry::new() 每个测试一份互不干扰，Registry::global() 全进程共享;
+        - registry.track("file", value) 得到 Tracked<T>，通过 Deref 使用内部的值，
+          Tracked 被 drop 时先 drop 内部的值，再记录事件;
+        - 检查: live() 还未 drop 的对象，drop_order() drop 顺序，
+          assert_drop_order(&[...]) / assert_no_leaks() 不符合时 panic 并附上时间线;
+        - timeline(): 按时间输出全部事件;
+    why: 文件、锁等 RAII 守卫提前 drop 或一直不 drop 都很难发现，用它把顺序固定下来。
+*/
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Created,
+    Dropped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub seq: u64,
+    pub at: Duration, // 相对 Registry 创建时刻
+    pub id: u64,
+    pub label: String,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            EventKind::Created => "创建",
+            EventKind::Dropped => "释放",
+        };
+        write!(f, "{:>4} +{:>10.6}s  {kind}  #{} {}", self.seq, self.at.as_secs_f64(), self.id, self.label)
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    start: Instant,
+    next_id: u64,
+    events: Vec<Event>,
+    live: BTreeMap<u64, String>,
+}
+
+impl Inner {
+    fn push(&mut self, id: u64, label: String, kind: EventKind) {
+        let event = Event { seq: self.events.len() as u64 + 1, at: self.start.elapsed(), id, label, kind };
+        self.events.push(event);
+    }
+}
+
+// 克隆后共享同一份记录
+#[derive(Debug, Clone)]
+pub struct Registry {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        let inner = Inner { start: Instant::now(), next_id: 1, events: Vec::new(), live: BTreeMap::new() };
+        Registry { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    pub fn global() -> &'static Registry {
+        static GLOBAL: OnceLock<Registry> = OnceLock::new();
+        GLOBAL.get_or_init(Registry::new)
+    }
+
+    // drop 中 panic 会让锁中毒，记录本身仍然可用
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn track<T>(&self, label: impl Into<String>, value: T) -> Tracked<T> {
+        let label = label.into();
+        let mut inner = self.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.live.insert(id, label.clone());
+        inner.push(id, label, EventKind::Created);
+        Tracked { value: Some(value), id, registry: self.clone() }
+    }
+
+    fn record_drop(&self, id: u64) {
+        let mut inner = self.lock();
+        if let Some(label) = inner.live.remove(&id) {
+            inner.push(id, label, EventKind::Dropped);
+        }
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.lock().events.clone()
+    }
+
+    // (id, 标签)，按创建顺序
+    pub fn live(&self) -> Vec<(u64, String)> {
+        self.lock().live.iter().map(|(id, label)| (*id, label.clone())).collect()
+    }
+
+    pub fn is_live(&self, label: &str) -> bool {
+        self.lock().live.values().any(|l| l == label)
+    }
+
+    pub fn drop_order(&self) -> Vec<String> {
+        self.lock()
+            .events
+            .iter()
+            .filter(|e| e.kind == EventKind::Dropped)
+            .map(|e| e.label.clone())
+            .collect()
+    }
+
+    pub fn timeline(&self) -> String {
+        self.lock().events.iter().map(|e| format!("{e}\n")).collect()
+    }
+
+    #[track_caller]
+    pub fn assert_drop_order(&self, expected: &[&str]) {
+        let actual = self.drop_order();
+        if actual != expected {
+            panic!("drop 顺序不符\n  期望: {expected:?}\n  实际: {actual:?}\n时间线:\n{}", self.timeline());
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        let live = self.live();
+        if !live.is_empty() {
+            let labels: Vec<String> = live.iter().map(|(id, label)| format!("#{id} {label}")).collect();
+            panic!("{} 个对象没有被 drop: {}\n时间线:\n{}", live.len(), labels.join(", "), self.timeline());
+        }
+    }
+}
+
+// 在 Registry 中登记过的值
+pub struct Tracked<T> {
+    // 只在 drop 中取出，保证内部的值先于事件记录被 drop
+    value: Option<T>,
+    id: u64,
+    registry: Registry,
+}
+
+impl<T> Tracked<T> {
+    // 登记到全局 Registry
+    pub fn new(label: impl Into<String>, value: T) -> Self {
+        Registry::global().track(label, value)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("值只在 drop 中取出")
+    }
+}
+
+impl<T> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("值只在 drop 中取出")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tracked").field("id", &self.id).field("value", &**self).finish()
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        drop(self.value.take());
+        self.registry.record_drop(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locals_drop_in_reverse_order() {
+        let registry = Registry::new();
+        {
+            let _a = registry.track("a", 1);
+            let _b = registry.track("b", 2);
+            let c = registry.track("c", 3);
+            drop(c);
+        }
+        registry.assert_drop_order(&["c", "b", "a"]);
+        registry.assert_no_leaks();
+    }
+
+    #[test]
+    fn fields_and_vec_elements_drop_in_declaration_order() {
+        struct Pair {
+            _first: Tracked<&'static str>,
+            _second: Tracked<&'static str>,
+        }
+        let registry = Registry::new();
+        let pair = Pair { _second: registry.track("second", "y"), _first: registry.track("first", "x") };
+        let items: Vec<_> = ["v0", "v1"].into_iter().map(|l| registry.track(l, ())).collect();
+        drop(pair);
+        drop(items);
+        registry.assert_drop_order(&["first", "second", "v0", "v1"]);
+    }
+
+    #[test]
+    fn inner_value_drops_before_outer_event() {
+        let registry = Registry::new();
+        let outer = registry.track("outer", registry.track("inner", 0));
+        assert_eq!(**outer, 0);
+        drop(outer);
+        registry.assert_drop_order(&["inner", "outer"]);
+        let kinds: Vec<EventKind> = registry.events().iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, [EventKind::Created, EventKind::Created, EventKind::Dropped, EventKind::Dropped]);
+    }
+
+    #[test]
+    fn registries_are_independent() {
+        let first = Registry::new();
+        let second = Registry::new();
+        let _kept = first.track("kept", ());
+        drop(second.track("gone", ()));
+        second.assert_no_leaks();
+        assert!(first.is_live("kept"));
+        assert!(!second.is_live("gone"));
+    }
+
+    #[test]
+    #[should_panic(expected = "drop 顺序不符")]
+    fn wrong_drop_order_panics() {
+        let registry = Registry::new();
+        {
+            let _a = registry.track("a", ());
+            let _b = registry.track("b", ());
+        }
+        registry.assert_drop_order(&["a", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 个对象没有被 drop: #2 leaked")]
+    fn forgotten_value_is_reported_as_leak() {
+        let registry = Registry::new();
+        drop(registry.track("dropped", ()));
+        std::mem::forget(registry.track("leaked", ()));
+        registry.assert_no_leaks();
+    }
+}
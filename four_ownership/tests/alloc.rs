@@ -0,0 +1,95 @@
+// 安装计数分配器，验证 "引用零成本"、"clone 会分配" 等结论: cargo test --test alloc
+use std::sync::mpsc;
+use std::thread;
+
+use four_ownership::alloc::{AllocStats, CountingAlloc, assert_no_alloc, is_installed, measure};
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or("")
+}
+
+#[test]
+fn allocator_is_installed() {
+    assert!(is_installed());
+}
+
+#[test]
+fn measure_counts_one_allocation() {
+    let (v, stats) = measure(|| vec![0u8; 100]);
+    assert_eq!(v.len(), 100);
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.bytes_allocated, 100);
+    assert_eq!(stats.net_bytes(), 100);
+    assert_eq!(stats.peak_bytes, 100);
+
+    let ((), stats) = measure(|| drop(v));
+    assert_eq!(stats, AllocStats { deallocations: 1, bytes_freed: 100, ..AllocStats::default() });
+}
+
+#[test]
+fn borrowing_does_not_allocate_but_clone_does() {
+    let s = String::from("solana sui");
+    let word = assert_no_alloc(|| first_word(&s));
+    assert_eq!(word, "solana");
+
+    let (copy, stats) = measure(|| s.clone());
+    assert_eq!(copy, s);
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.bytes_allocated, s.len() as u64);
+}
+
+#[test]
+fn growth_is_counted_as_reallocation() {
+    let ((), stats) = measure(|| {
+        let mut v: Vec<u64> = Vec::with_capacity(1);
+        v.extend(0..64);
+    });
+    assert_eq!(stats.allocations, 1);
+    assert!(stats.reallocations > 0);
+    assert_eq!(stats.net_bytes(), 0);
+    assert!(stats.peak_bytes >= 64 * 8);
+}
+
+#[test]
+fn nested_measure_counts_inner_in_outer() {
+    let ((_inner, inner), outer) = measure(|| {
+        let _a = Box::new(1u32);
+        measure(|| Box::new(2u64))
+    });
+    assert_eq!(inner.allocations, 1);
+    assert_eq!(outer.allocations, 2);
+}
+
+#[test]
+#[should_panic(expected = "不应分配内存")]
+fn assert_no_alloc_panics_on_allocation() {
+    assert_no_alloc(|| String::from("hot path"));
+}
+
+#[test]
+fn other_threads_are_not_counted() {
+    let ((), stats) = measure(|| {
+        thread::scope(|scope| {
+            scope.spawn(|| drop(std::hint::black_box(vec![0u8; 1024])));
+        });
+    });
+    // 线程本身的创建会在当前线程分配少量内存，但 1024 字节的 Vec 不计入
+    assert!(stats.bytes_allocated < 1024, "{stats}");
+}
+
+#[test]
+fn free_on_another_thread_goes_negative_there() {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    let receiver = thread::spawn(move || {
+        let v = rx.recv().unwrap();
+        measure(|| drop(v)).1
+    });
+    let (v, sent) = measure(|| vec![0u8; 4096]);
+    tx.send(v).unwrap();
+    let freed = receiver.join().unwrap();
+    assert_eq!(sent.net_bytes(), 4096);
+    assert_eq!(freed.net_bytes(), -4096);
+}
@@ -0,0 +1,445 @@
+// ---------------- 本地认证 ----------------
+/*
+    what: 为 User 增加密码凭据，供无法调用身份服务的命令行工具使用。
+    how:
+        - 密码: PBKDF2-HMAC-SHA256，随机 16 字节盐，迭代次数随哈希一起保存;
+        - 编码格式: pbkdf2-sha256$<迭代次数>$<盐 hex>$<哈希 hex>;
+          迭代次数不能低于 MIN_ITERATIONS，防止篡改文件把哈希降级成 1 次迭代;
+        - 登录: 成功时 sign_in_count + 1 并清零失败次数;
+          连续失败达到上限后锁定，冷却时间过后自动解锁;
+        - Credentials: 哈希 + 失败次数 + 锁定截止时间，encode/decode 为一行文本，
+          由 UserRegistry 随用户一起保存，命令行每次运行都能延续锁定状态;
+        - 时钟: 通过 Clock trait 注入，ManualClock 用于测试时间相关逻辑;
+*/
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+
+use crate::user::User;
+
+const SCHEME: &str = "pbkdf2-sha256";
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+const MIN_PASSWORD_CHARS: usize = 8;
+
+// OWASP 对 PBKDF2-HMAC-SHA256 的建议值
+pub const DEFAULT_ITERATIONS: u32 = 600_000;
+// NIST SP 800-63B 要求的下限
+pub const MIN_ITERATIONS: u32 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    PasswordTooShort,
+    TooFewIterations(u32),
+    InvalidPassword { remaining_attempts: u32 },
+    LockedOut { retry_after: Duration },
+    Inactive,
+    Random,
+    MalformedHash(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::PasswordTooShort => write!(f, "密码至少需要 {MIN_PASSWORD_CHARS} 个字符"),
+            AuthError::TooFewIterations(n) => write!(f, "迭代次数 {n} 低于下限 {MIN_ITERATIONS}"),
+            AuthError::InvalidPassword { remaining_attempts } => {
+                write!(f, "密码错误，还可尝试 {remaining_attempts} 次")
+            }
+            AuthError::LockedOut { retry_after } => {
+                write!(f, "账号已锁定，请 {} 秒后重试", retry_after.as_secs().max(1))
+            }
+            AuthError::Inactive => write!(f, "账号已停用"),
+            AuthError::Random => write!(f, "无法生成随机盐"),
+            AuthError::MalformedHash(s) => write!(f, "密码哈希格式错误: {s}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+// ---------- 时钟 ----------
+
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+// 手动推进的时钟: Cell 允许通过 &self 修改时间
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Cell<SystemTime>,
+}
+
+impl ManualClock {
+    pub fn new(start: SystemTime) -> Self {
+        ManualClock { now: Cell::new(start) }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH)
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        self.now.get()
+    }
+}
+
+// 引用也可以作为时钟，测试时 Authenticator 借用 ManualClock，外部仍可推进时间
+impl<C: Clock> Clock for &C {
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+// ---------- 密码哈希 ----------
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    iterations: u32,
+    salt: [u8; SALT_LEN],
+    hash: [u8; HASH_LEN],
+}
+
+// 不打印哈希内容
+impl fmt::Debug for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PasswordHash({SCHEME}, {} 次迭代)", self.iterations)
+    }
+}
+
+impl PasswordHash {
+    pub fn new(password: &str, iterations: u32) -> Result<Self, AuthError> {
+        if password.chars().count() < MIN_PASSWORD_CHARS {
+            return Err(AuthError::PasswordTooShort);
+        }
+        if iterations < MIN_ITERATIONS {
+            return Err(AuthError::TooFewIterations(iterations));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        getrandom::fill(&mut salt).map_err(|_| AuthError::Random)?;
+        Ok(Self::with_salt(password, salt, iterations))
+    }
+
+    fn with_salt(password: &str, salt: [u8; SALT_LEN], iterations: u32) -> Self {
+        let mut hash = [0u8; HASH_LEN];
+        pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, iterations, &mut hash);
+        PasswordHash { iterations, salt, hash }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        let candidate = Self::with_salt(password, self.salt, self.iterations);
+        // 常量时间比较，避免通过耗时猜测哈希前缀
+        self.hash
+            .iter()
+            .zip(candidate.hash.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{SCHEME}${}${}${}", self.iterations, to_hex(&self.salt), to_hex(&self.hash))
+    }
+
+    pub fn decode(s: &str) -> Result<Self, AuthError> {
+        let err = || AuthError::MalformedHash(s.to_string());
+        let parts: Vec<&str> = s.split('$').collect();
+        let [SCHEME, iterations, salt, hash] = parts[..] else {
+            return Err(err());
+        };
+        let iterations: u32 = iterations.parse().map_err(|_| err())?;
+        if iterations < MIN_ITERATIONS {
+            return Err(AuthError::TooFewIterations(iterations));
+        }
+        Ok(PasswordHash {
+            iterations,
+            salt: from_hex(salt).ok_or_else(err)?,
+            hash: from_hex(hash).ok_or_else(err)?,
+        })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    if s.len() != N * 2 || !s.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+// ---------- 账号与登录 ----------
+
+// 需要持久化的凭据和锁定状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub password: PasswordHash,
+    pub failed_attempts: u32,
+    pub locked_until: Option<SystemTime>,
+}
+
+impl Credentials {
+    pub fn new(password: PasswordHash) -> Self {
+        Credentials { password, failed_attempts: 0, locked_until: None }
+    }
+
+    // <哈希>\t<失败次数>\t<锁定截止的 Unix 秒数，未锁定为 ->
+    pub fn encode(&self) -> String {
+        let locked = match self.locked_until {
+            // 精度到秒，锁定可能提前不到 1 秒结束
+            Some(until) => until.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs().to_string(),
+            None => "-".to_string(),
+        };
+        format!("{}\t{}\t{locked}", self.password.encode(), self.failed_attempts)
+    }
+
+    pub fn decode(s: &str) -> Result<Self, AuthError> {
+        let err = || AuthError::MalformedHash(s.to_string());
+        let [hash, failed, locked] = s.split('\t').collect::<Vec<_>>()[..] else {
+            return Err(err());
+        };
+        let locked_until = match locked {
+            "-" => None,
+            secs => {
+                let secs = secs.parse().map_err(|_| err())?;
+                Some(SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs)).ok_or_else(err)?)
+            }
+        };
+        Ok(Credentials {
+            password: PasswordHash::decode(hash)?,
+            failed_attempts: failed.parse().map_err(|_| err())?,
+            locked_until,
+        })
+    }
+}
+
+// 组合而非修改课程中的 User: 账号 = 用户 + 凭据 + 锁定状态
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub user: User,
+    pub credentials: Credentials,
+}
+
+impl Account {
+    pub fn new(user: User, password: PasswordHash) -> Self {
+        Account { user, credentials: Credentials::new(password) }
+    }
+
+    pub fn password_hash(&self) -> &PasswordHash {
+        &self.credentials.password
+    }
+
+    pub fn failed_attempts(&self) -> u32 {
+        self.credentials.failed_attempts
+    }
+
+    pub fn locked_until(&self) -> Option<SystemTime> {
+        self.credentials.locked_until
+    }
+
+    pub fn set_password(&mut self, password: PasswordHash) {
+        self.credentials.password = password;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    pub max_failures: u32,
+    pub cooldown: Duration,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        LockoutPolicy { max_failures: 5, cooldown: Duration::from_secs(15 * 60) }
+    }
+}
+
+#[derive(Debug)]
+pub struct Authenticator<C: Clock = SystemClock> {
+    pub policy: LockoutPolicy,
+    pub iterations: u32,
+    clock: C,
+}
+
+impl Default for Authenticator {
+    fn default() -> Self {
+        Authenticator::new(SystemClock)
+    }
+}
+
+impl<C: Clock> Authenticator<C> {
+    pub fn new(clock: C) -> Self {
+        Authenticator { policy: LockoutPolicy::default(), iterations: DEFAULT_ITERATIONS, clock }
+    }
+
+    pub fn hash_password(&self, password: &str) -> Result<PasswordHash, AuthError> {
+        PasswordHash::new(password, self.iterations)
+    }
+
+    pub fn create_account(&self, user: User, password: &str) -> Result<Account, AuthError> {
+        Ok(Account::new(user, self.hash_password(password)?))
+    }
+
+    // 登录: 成功返回新的 sign_in_count
+    pub fn sign_in(&self, account: &mut Account, password: &str) -> Result<u64, AuthError> {
+        if !account.user.active {
+            return Err(AuthError::Inactive);
+        }
+        let now = self.clock.now();
+        let state = &mut account.credentials;
+        if let Some(until) = state.locked_until {
+            match until.duration_since(now) {
+                Ok(retry_after) if !retry_after.is_zero() => {
+                    return Err(AuthError::LockedOut { retry_after });
+                }
+                // 冷却结束，重新计数
+                _ => {
+                    state.locked_until = None;
+                    state.failed_attempts = 0;
+                }
+            }
+        }
+
+        if state.password.verify(password) {
+            state.failed_attempts = 0;
+            account.user.sign_in_count += 1;
+            return Ok(account.user.sign_in_count);
+        }
+
+        state.failed_attempts = state.failed_attempts.saturating_add(1);
+        if state.failed_attempts >= self.policy.max_failures {
+            let until = saturating_add(now, self.policy.cooldown);
+            state.locked_until = Some(until);
+            return Err(AuthError::LockedOut { retry_after: until.duration_since(now).unwrap_or_default() });
+        }
+        Err(AuthError::InvalidPassword {
+            remaining_attempts: self.policy.max_failures - state.failed_attempts,
+        })
+    }
+}
+
+// now + cooldown 超出 SystemTime 的范围时，锁到能表示的最晚时间附近
+fn saturating_add(time: SystemTime, mut by: Duration) -> SystemTime {
+    loop {
+        if let Some(t) = time.checked_add(by) {
+            return t;
+        }
+        by /= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &str = "correct horse";
+
+    fn setup(clock: &ManualClock) -> (Authenticator<&ManualClock>, Account) {
+        let mut auth = Authenticator::new(clock);
+        auth.iterations = MIN_ITERATIONS;
+        auth.policy = LockoutPolicy { max_failures: 3, cooldown: Duration::from_secs(60) };
+        let user = User::new("sui@example.com", "sui").unwrap();
+        let account = auth.create_account(user, PASSWORD).unwrap();
+        (auth, account)
+    }
+
+    #[test]
+    fn hash_round_trip_and_verify() {
+        let hash = PasswordHash::new(PASSWORD, MIN_ITERATIONS).unwrap();
+        let decoded = PasswordHash::decode(&hash.encode()).unwrap();
+        assert_eq!(decoded, hash);
+        assert!(decoded.verify(PASSWORD));
+        assert!(!decoded.verify("wrong horse"));
+        assert_eq!(PasswordHash::new("short", MIN_ITERATIONS), Err(AuthError::PasswordTooShort));
+    }
+
+    #[test]
+    fn low_iteration_counts_are_rejected() {
+        assert_eq!(PasswordHash::new(PASSWORD, 1), Err(AuthError::TooFewIterations(1)));
+        let hash = PasswordHash::new(PASSWORD, MIN_ITERATIONS).unwrap().encode();
+        let downgraded = hash.replacen(&format!("${MIN_ITERATIONS}$"), "$1$", 1);
+        assert_eq!(PasswordHash::decode(&downgraded), Err(AuthError::TooFewIterations(1)));
+        assert!(matches!(PasswordHash::decode("pbkdf2-sha256$x$00$00"), Err(AuthError::MalformedHash(_))));
+    }
+
+    #[test]
+    fn lockout_and_cooldown_with_manual_clock() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let (auth, mut account) = setup(&clock);
+
+        assert_eq!(auth.sign_in(&mut account, "nope nope"), Err(AuthError::InvalidPassword { remaining_attempts: 2 }));
+        assert_eq!(auth.sign_in(&mut account, "nope nope"), Err(AuthError::InvalidPassword { remaining_attempts: 1 }));
+        let retry_after = Duration::from_secs(60);
+        assert_eq!(auth.sign_in(&mut account, "nope nope"), Err(AuthError::LockedOut { retry_after }));
+        // 锁定期间正确密码也不行
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(auth.sign_in(&mut account, PASSWORD), Err(AuthError::LockedOut { retry_after: Duration::from_secs(1) }));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(auth.sign_in(&mut account, PASSWORD), Ok(1));
+        assert_eq!(account.failed_attempts(), 0);
+        assert_eq!(account.locked_until(), None);
+    }
+
+    #[test]
+    fn lockout_survives_encode_decode() {
+        let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        let (auth, mut account) = setup(&clock);
+        for _ in 0..3 {
+            let _ = auth.sign_in(&mut account, "nope nope");
+        }
+        let restored = Credentials::decode(&account.credentials.encode()).unwrap();
+        assert_eq!(restored, account.credentials);
+        assert_eq!(restored.locked_until, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_060)));
+
+        let mut reloaded = Account { user: account.user.clone(), credentials: restored };
+        assert!(matches!(auth.sign_in(&mut reloaded, PASSWORD), Err(AuthError::LockedOut { .. })));
+    }
+
+    #[test]
+    fn huge_cooldown_does_not_overflow() {
+        let clock = ManualClock::default();
+        let (mut auth, mut account) = setup(&clock);
+        auth.policy.cooldown = Duration::MAX;
+        for _ in 0..2 {
+            let _ = auth.sign_in(&mut account, "nope nope");
+        }
+        assert!(matches!(auth.sign_in(&mut account, "nope nope"), Err(AuthError::LockedOut { .. })));
+        clock.advance(Duration::from_secs(365 * 24 * 3600));
+        assert!(matches!(auth.sign_in(&mut account, PASSWORD), Err(AuthError::LockedOut { .. })));
+    }
+
+    #[test]
+    fn inactive_accounts_cannot_sign_in() {
+        let clock = ManualClock::default();
+        let (auth, mut account) = setup(&clock);
+        account.user.active = false;
+        assert_eq!(auth.sign_in(&mut account, PASSWORD), Err(AuthError::Inactive));
+    }
+}
@@ -0,0 +1,30 @@
+// 班级成绩报告: cargo run --bin report -- <成绩.csv> [评分配置]
+use std::env;
+use std::process;
+
+use one_base::grading::{ClassReport, GradingError, GradingScale};
+use one_base::{log, style};
+
+fn run(args: &[String]) -> Result<(), GradingError> {
+    let (csv, scale) = match args {
+        [csv] => (csv, GradingScale::default()),
+        [csv, config] => (csv, GradingScale::load(config)?),
+        _ => {
+            println!("用法: report <成绩.csv> [评分配置]");
+            return Ok(());
+        }
+    };
+    print!("{}", ClassReport::load(csv, &scale)?);
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = log::init_from_env() {
+        eprintln!("{e}");
+    }
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", style::ERROR.paint(format!("错误: {e}")).for_stderr());
+        process::exit(1);
+    }
+}
@@ -0,0 +1,71 @@
+// 账号管理工具: cargo run --bin users -- <文件> <命令> [参数]
+use std::env;
+use std::path::Path;
+use std::process;
+
+use one_base::auth::Authenticator;
+use one_base::registry::{RegistryError, UserRegistry};
+use one_base::table::{Align, Style, Table};
+use one_base::user::User;
+use one_base::{log, style};
+
+const USAGE: &str =
+    "用法: users <文件> list | add <邮箱> <用户名> | signin <邮箱> | passwd <邮箱> <密码> | login <邮箱> <密码> | deactivate <邮箱>";
+
+fn run(args: &[String]) -> Result<(), RegistryError> {
+    let [path, command, rest @ ..] = args else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+    let path = Path::new(path);
+    let mut registry = if path.exists() { UserRegistry::load(path)? } else { UserRegistry::new() };
+
+    match (command.as_str(), rest) {
+        ("list", []) => {
+            let users: Vec<User> = registry.iter().cloned().collect();
+            print!("{}", Table::from_rows(&users).align(3, Align::Right).style(Style::Box));
+            return Ok(());
+        }
+        ("add", [email, username]) => {
+            let user = registry.create(email, username)?;
+            println!("已创建: {} <{}>", user.username, user.email);
+        }
+        ("signin", [email]) => {
+            let count = registry.record_sign_in(email)?;
+            println!("登录次数: {count}");
+        }
+        ("passwd", [email, password]) => {
+            registry.set_password(email, Authenticator::default().hash_password(password)?)?;
+            println!("已设置密码: {email}");
+        }
+        ("login", [email, password]) => {
+            let mut account = registry.account(email)?;
+            let result = Authenticator::default().sign_in(&mut account, password);
+            // 失败次数和锁定状态也要保存，否则每次运行都会重新计数
+            registry.update_account(account)?;
+            registry.save(path)?;
+            println!("登录次数: {}", result?);
+            return Ok(());
+        }
+        ("deactivate", [email]) => {
+            registry.deactivate(email)?;
+            println!("已停用: {email}");
+        }
+        _ => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+    }
+    registry.save(path)
+}
+
+fn main() {
+    if let Err(e) = log::init_from_env() {
+        eprintln!("{e}");
+    }
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", style::ERROR.paint(format!("错误: {e}")).for_stderr());
+        process::exit(1);
+    }
+}
@@ -0,0 +1,91 @@
+// 交互式售货机: cargo run --bin vending
+use std::io::{self, BufRead, Write};
+
+use one_base::coin::Coin;
+use one_base::money::Money;
+use one_base::vending::{Product, VendingMachine};
+use one_base::{log, style};
+
+const HELP: &str = "命令: insert <penny|nickel|dime|quarter> | select <货道> | refund | status | log | help | quit";
+
+fn stocked_machine() -> VendingMachine {
+    let mut vm = VendingMachine::new();
+    let setup = [
+        ("A1", "可乐", 65, 5),
+        ("A2", "矿泉水", 50, 5),
+        ("B1", "薯片", 85, 3),
+    ];
+    for (slot, name, price, stock) in setup {
+        let product = Product::new(name, Money::from_minor(price), stock).expect("价格不为负");
+        vm.set_product(slot, product).expect("新机器处于维护状态");
+    }
+    for (coin, count) in [(Coin::Quarter, 8), (Coin::Dime, 10), (Coin::Nickel, 10), (Coin::Penny, 20)] {
+        vm.load_coins(coin, count).expect("新机器处于维护状态");
+    }
+    vm.close_service().expect("新机器处于维护状态");
+    vm
+}
+
+fn print_status(vm: &VendingMachine) {
+    println!("状态: {}，已投: {}", vm.state().kind(), vm.credit());
+    if vm.exact_change_only() {
+        println!("{}", style::WARNING.paint("** 仅收零钱 **"));
+    }
+    for (slot, p) in vm.products() {
+        println!("  {slot}  {}  {}  库存 {}", p.name, p.price, p.stock);
+    }
+}
+
+fn main() {
+    if let Err(e) = log::init_from_env() {
+        eprintln!("{e}");
+    }
+    let mut vm = stocked_machine();
+    println!("{HELP}");
+    print_status(&vm);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let mut parts = line.split_whitespace();
+        let result = match (parts.next(), parts.next()) {
+            (Some("insert"), Some(arg)) => match arg.parse::<Coin>() {
+                Ok(coin) => vm.insert(coin).map(|credit| println!("已投: {credit}")),
+                Err(e) => {
+                    println!("{e}");
+                    continue;
+                }
+            },
+            (Some("select"), Some(slot)) => vm.select(slot).map(|vend| {
+                println!("{}", style::SUCCESS.paint(format!("出货: {}", vend.product)));
+                if !vend.change.is_empty() {
+                    let change: Vec<String> = vend.change.iter().map(|c| c.to_string()).collect();
+                    println!("找零: {}", change.join(", "));
+                }
+            }),
+            (Some("refund"), None) => vm.refund().map(|coins| println!("退币: {coins:?}")),
+            (Some("status"), None) => {
+                print_status(&vm);
+                Ok(())
+            }
+            (Some("log"), None) => {
+                vm.audit_log().iter().for_each(|entry| println!("{entry}"));
+                Ok(())
+            }
+            (Some("quit"), None) => break,
+            (None, _) => continue,
+            _ => {
+                println!("{HELP}");
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            println!("{}", style::ERROR.paint(format!("错误: {e}")));
+        }
+    }
+}
@@ -0,0 +1,316 @@
+// ---------------- 中文数字 ----------------
+/*
+    what: 整数与中文数字互转，以及金额的元角分大写，用于打印发票。
+    规则:
+        - 小写: 九千五百二十七; 大写: 玖仟伍佰贰拾柒;
+        - 每 4 位一节，节单位为 万、亿，亿以上继续嵌套（一万亿、一亿亿）;
+        - 零: 节内连续的 0 只读一个零，末尾的 0 不读;
+              低一级的节不足千位时补零，如 10001 一万零一、100010000 一亿零一万;
+        - 小写中 10~19 开头的 一十 简写为 十（十五、十万），大写保留 壹拾;
+        - 解析两种写法都接受，也接受 两、〇 以及 二〇二六 这类逐位写法;
+          数字后面紧跟数字（一二万）、节内单位重复或不递减（五十五十、十十）时报错;
+          万 在一个 亿 节内只能出现一次; 亿 按嵌套理解（一万亿、一亿亿、九亿零一亿），不受这条限制;
+*/
+
+use std::fmt;
+
+use crate::money::{Cny, Money};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumeralStyle {
+    #[default]
+    Simple,
+    Financial,
+}
+
+const SIMPLE_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const FINANCIAL_DIGITS: [char; 10] = ['零', '壹', '贰', '叁', '肆', '伍', '陆', '柒', '捌', '玖'];
+const SIMPLE_UNITS: [char; 3] = ['十', '百', '千'];
+const FINANCIAL_UNITS: [char; 3] = ['拾', '佰', '仟'];
+
+const WAN: i128 = 10_000;
+const YI: i128 = 100_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumeralError {
+    Empty,
+    InvalidChar(char),
+    // 字符本身合法，但出现的位置不对: 一二万、五十五十
+    Misplaced(char),
+    Overflow,
+}
+
+impl fmt::Display for NumeralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumeralError::Empty => write!(f, "没有数字"),
+            NumeralError::InvalidChar(c) => write!(f, "无法识别的字符: {c}"),
+            NumeralError::Misplaced(c) => write!(f, "{c} 的位置不对"),
+            NumeralError::Overflow => write!(f, "数值溢出"),
+        }
+    }
+}
+
+impl std::error::Error for NumeralError {}
+
+impl NumeralStyle {
+    fn digits(self) -> &'static [char; 10] {
+        match self {
+            NumeralStyle::Simple => &SIMPLE_DIGITS,
+            NumeralStyle::Financial => &FINANCIAL_DIGITS,
+        }
+    }
+
+    fn units(self) -> &'static [char; 3] {
+        match self {
+            NumeralStyle::Simple => &SIMPLE_UNITS,
+            NumeralStyle::Financial => &FINANCIAL_UNITS,
+        }
+    }
+}
+
+pub fn to_chinese<T: Into<i128>>(n: T, style: NumeralStyle) -> String {
+    let n: i128 = n.into();
+    let mut out = String::new();
+    if n < 0 {
+        out.push('负');
+    }
+    let body = format_unsigned(n.unsigned_abs(), style);
+    // 十五、十万: 只在最开头省略 一
+    match (style, body.strip_prefix("一十")) {
+        (NumeralStyle::Simple, Some(rest)) => {
+            out.push('十');
+            out.push_str(rest);
+        }
+        _ => out.push_str(&body),
+    }
+    out
+}
+
+fn format_unsigned(n: u128, style: NumeralStyle) -> String {
+    if n == 0 {
+        return style.digits()[0].to_string();
+    }
+    let mut out = String::new();
+    write_section(&mut out, n, style);
+    out
+}
+
+// 按 亿 → 万 → 千位节 逐级拆分
+fn write_section(out: &mut String, n: u128, style: NumeralStyle) {
+    let (unit_value, unit) = if n >= YI as u128 {
+        (YI as u128, '亿')
+    } else if n >= WAN as u128 {
+        (WAN as u128, '万')
+    } else {
+        write_group(out, n as u16, style);
+        return;
+    };
+    let (high, low) = (n / unit_value, n % unit_value);
+    write_section(out, high, style);
+    out.push(unit);
+    if low > 0 {
+        if low < unit_value / 10 {
+            out.push(style.digits()[0]);
+        }
+        write_section(out, low, style);
+    }
+}
+
+// 0 < n < 10000
+fn write_group(out: &mut String, n: u16, style: NumeralStyle) {
+    let digits = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10];
+    let mut pending_zero = false;
+    let mut started = false;
+    for (i, &d) in digits.iter().enumerate() {
+        if d == 0 {
+            pending_zero = started;
+            continue;
+        }
+        if pending_zero {
+            out.push(style.digits()[0]);
+            pending_zero = false;
+        }
+        out.push(style.digits()[d as usize]);
+        if i < 3 {
+            out.push(style.units()[2 - i]);
+        }
+        started = true;
+    }
+}
+
+fn digit_value(c: char) -> Option<i128> {
+    match c {
+        '零' | '〇' => Some(0),
+        '一' | '壹' => Some(1),
+        '二' | '贰' | '两' => Some(2),
+        '三' | '叁' => Some(3),
+        '四' | '肆' => Some(4),
+        '五' | '伍' => Some(5),
+        '六' | '陆' => Some(6),
+        '七' | '柒' => Some(7),
+        '八' | '捌' => Some(8),
+        '九' | '玖' => Some(9),
+        _ => None,
+    }
+}
+
+fn unit_value(c: char) -> Option<i128> {
+    match c {
+        '十' | '拾' => Some(10),
+        '百' | '佰' => Some(100),
+        '千' | '仟' => Some(1000),
+        '万' => Some(WAN),
+        '亿' => Some(YI),
+        _ => None,
+    }
+}
+
+pub fn parse_chinese(s: &str) -> Result<i128, NumeralError> {
+    let s = s.trim();
+    let (negative, body) = match s.strip_prefix('负') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    if body.is_empty() {
+        return Err(NumeralError::Empty);
+    }
+    for c in body.chars() {
+        if digit_value(c).is_none() && unit_value(c).is_none() {
+            return Err(NumeralError::InvalidChar(c));
+        }
+    }
+
+    let value = if body.chars().all(|c| digit_value(c).is_some()) {
+        // 逐位写法: 二〇二六
+        body.chars().try_fold(0i128, |acc, c| {
+            acc.checked_mul(10)
+                .and_then(|v| v.checked_add(digit_value(c).unwrap_or(0)))
+                .ok_or(NumeralError::Overflow)
+        })?
+    } else {
+        parse_with_units(body)?
+    };
+    Ok(if negative { -value } else { value })
+}
+
+// total: 亿 以上的部分; section: 当前 万 节; number: 最近读到的数字
+// small: 当前节内上一个 十/百/千，后面的单位必须更小; wan: 当前 亿 节内已经出现过 万
+fn parse_with_units(body: &str) -> Result<i128, NumeralError> {
+    let overflow = |v: Option<i128>| v.ok_or(NumeralError::Overflow);
+    let (mut total, mut section, mut number) = (0i128, 0i128, 0i128);
+    let mut small = WAN;
+    let mut wan = false;
+    let mut prev_digit = false;
+    for c in body.chars() {
+        if let Some(d) = digit_value(c) {
+            // 零 之后可以接数字: 一千零五; 其他数字后面不能再接数字
+            if prev_digit && number != 0 {
+                return Err(NumeralError::Misplaced(c));
+            }
+            number = d;
+            prev_digit = true;
+            continue;
+        }
+        let current = overflow(section.checked_add(number))?;
+        match unit_value(c) {
+            Some(YI) => {
+                total = overflow(total.checked_add(current).and_then(|v| v.checked_mul(YI)))?;
+                section = 0;
+                small = WAN;
+                wan = false;
+            }
+            Some(WAN) => {
+                if wan {
+                    return Err(NumeralError::Misplaced(c));
+                }
+                section = overflow(current.checked_mul(WAN))?;
+                small = WAN;
+                wan = true;
+            }
+            Some(unit) => {
+                if unit >= small {
+                    return Err(NumeralError::Misplaced(c));
+                }
+                small = unit;
+                // 十 前面没有数字时视为 一十
+                let value = if number == 0 { 1 } else { number } * unit;
+                section = overflow(section.checked_add(value))?;
+            }
+            None => return Err(NumeralError::InvalidChar(c)),
+        }
+        number = 0;
+        prev_digit = false;
+    }
+    overflow(section.checked_add(number).and_then(|v| total.checked_add(v)))
+}
+
+// 金额大写: 玖仟伍佰贰拾柒元伍角整、壹元零伍分
+pub fn format_amount(amount: Money<Cny>) -> String {
+    let minor = amount.minor();
+    let mut out = String::new();
+    if minor < 0 {
+        out.push('负');
+    }
+    let abs = minor.unsigned_abs();
+    let (yuan, jiao, fen) = (abs / 100, abs / 10 % 10, abs % 10);
+    let digit = |d: u64| FINANCIAL_DIGITS[d as usize];
+
+    if yuan > 0 || abs == 0 {
+        out.push_str(&format_unsigned(yuan as u128, NumeralStyle::Financial));
+        out.push('元');
+    }
+    if jiao > 0 {
+        out.push(digit(jiao));
+        out.push('角');
+    } else if fen > 0 && yuan > 0 {
+        out.push('零');
+    }
+    if fen > 0 {
+        out.push(digit(fen));
+        out.push('分');
+    } else {
+        out.push('整');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for n in [0i128, 5, 10, 15, 110, 1005, 10001, 100010000, 9527, -42, 1_000_000_000_000, i64::MAX as i128] {
+            for style in [NumeralStyle::Simple, NumeralStyle::Financial] {
+                let text = to_chinese(n, style);
+                assert_eq!(parse_chinese(&text), Ok(n), "{text}");
+            }
+        }
+    }
+
+    #[test]
+    fn accepted_spellings() {
+        assert_eq!(parse_chinese("二〇二六"), Ok(2026));
+        assert_eq!(parse_chinese("两千零五十"), Ok(2050));
+        assert_eq!(parse_chinese("一万亿"), Ok(1_000_000_000_000));
+        assert_eq!(parse_chinese("一亿亿"), Ok(10_000_000_000_000_000));
+        assert_eq!(parse_chinese("一千五百万"), Ok(15_000_000));
+    }
+
+    #[test]
+    fn malformed_numerals_are_rejected() {
+        assert_eq!(parse_chinese("一二万"), Err(NumeralError::Misplaced('二')));
+        assert_eq!(parse_chinese("五十五十"), Err(NumeralError::Misplaced('十')));
+        assert_eq!(parse_chinese("十十十"), Err(NumeralError::Misplaced('十')));
+        assert_eq!(parse_chinese("一百一千"), Err(NumeralError::Misplaced('千')));
+        assert_eq!(parse_chinese("一万一万"), Err(NumeralError::Misplaced('万')));
+        assert_eq!(parse_chinese("负"), Err(NumeralError::Empty));
+        assert_eq!(parse_chinese("一百x"), Err(NumeralError::InvalidChar('x')));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        assert_eq!(parse_chinese("一亿亿亿亿亿"), Err(NumeralError::Overflow));
+    }
+}
@@ -0,0 +1,80 @@
+// ---------------- 硬币 Coin ----------------
+// 来自 enum 课程的模式匹配示例，整理为可复用的类型
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::money::{Money, Usd};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Coin {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter,
+}
+
+// 课程原函数: match 每个变体返回面值（美分）
+pub fn value_in_cents(coin: Coin) -> u8 {
+    match coin {
+        Coin::Penny => 1,
+        Coin::Nickel => 5,
+        Coin::Dime => 10,
+        Coin::Quarter => 25,
+    }
+}
+
+impl Coin {
+    // 按面值从大到小排列，找零时优先使用大面值
+    pub const ALL: [Coin; 4] = [Coin::Quarter, Coin::Dime, Coin::Nickel, Coin::Penny];
+
+    pub fn cents(self) -> u32 {
+        value_in_cents(self) as u32
+    }
+
+    // 以 Money 表示面值，可以累加出超过 255 分的合计
+    pub fn value(self) -> Money<Usd> {
+        Money::from_minor(value_in_cents(self) as i64)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Coin::Penny => "penny",
+            Coin::Nickel => "nickel",
+            Coin::Dime => "dime",
+            Coin::Quarter => "quarter",
+        }
+    }
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({}¢)", self.name(), self.cents())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCoinError(String);
+
+impl fmt::Display for ParseCoinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "无法识别的硬币: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCoinError {}
+
+// 支持名称或面值: "quarter" / "25"
+impl FromStr for Coin {
+    type Err = ParseCoinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "penny" | "1" => Ok(Coin::Penny),
+            "nickel" | "5" => Ok(Coin::Nickel),
+            "dime" | "10" => Ok(Coin::Dime),
+            "quarter" | "25" => Ok(Coin::Quarter),
+            other => Err(ParseCoinError(other.to_string())),
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// ---------------- 颜色 Color ----------------
+/*
+    what: 替代课程中的 struct Color(i32, i32, i32)，通道用 u8，不会出现负数或越界。
+    how:
+        - 解析: #RRGGBB、#RGB、#RRGGBBAA、rgb(r, g, b)、rgba(r, g, b, a);
+        - 颜色空间: RGB ↔ HSL / HSV;
+        - 合成: blend（alpha 叠加，source-over）、mix（线性插值）;
+        - 对比度: WCAG 相对亮度与对比度（4.5:1 为正文可读的最低要求）;
+        - 终端: nearest_ansi256 在 6×6×6 色块和 24 级灰度中找最接近的颜色;
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "无法解析颜色: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+// 色相 0~360，其余分量 0~1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f64,
+    pub s: f64,
+    pub v: f64,
+}
+
+// ANSI 256 色中 6×6×6 色块每个分量的取值
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    pub fn with_alpha(self, a: u8) -> Self {
+        Color { a, ..self }
+    }
+
+    fn channels(self) -> [f64; 3] {
+        [self.r, self.g, self.b].map(|c| c as f64 / 255.0)
+    }
+
+    fn from_unit(r: f64, g: f64, b: f64, a: u8) -> Self {
+        let to_u8 = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color { r: to_u8(r), g: to_u8(g), b: to_u8(b), a }
+    }
+
+    // ---------- 颜色空间 ----------
+
+    fn hue(self) -> (f64, f64, f64) {
+        let [r, g, b] = self.channels();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (h, max, min)
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        let s = if delta == 0.0 { 0.0 } else { delta / (1.0 - (2.0 * l - 1.0).abs()) };
+        Hsl { h, s, l }
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        let (h, max, min) = self.hue();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv { h, s, v: max }
+    }
+
+    // 由色相和色度计算 RGB，HSL 和 HSV 共用
+    fn from_hue(h: f64, chroma: f64, m: f64) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color::from_unit(r + m, g + m, b + m, 255)
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let chroma = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        Self::from_hue(hsl.h, chroma, hsl.l - chroma / 2.0)
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Self {
+        let chroma = hsv.v * hsv.s;
+        Self::from_hue(hsv.h, chroma, hsv.v - chroma)
+    }
+
+    // ---------- 合成 ----------
+
+    // self 叠加在 below 之上（source-over）
+    pub fn blend(self, below: Color) -> Color {
+        let top_a = self.a as f64 / 255.0;
+        let below_a = below.a as f64 / 255.0;
+        let out_a = top_a + below_a * (1.0 - top_a);
+        if out_a == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let [tr, tg, tb] = self.channels();
+        let [br, bg, bb] = below.channels();
+        let mix = |t: f64, b: f64| (t * top_a + b * below_a * (1.0 - top_a)) / out_a;
+        Color::from_unit(mix(tr, br), mix(tg, bg), mix(tb, bb), (out_a * 255.0).round() as u8)
+    }
+
+    // t = 0 得到 self，t = 1 得到 other
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color {
+            r: lerp(self.r, other.r),
+            g: lerp(self.g, other.g),
+            b: lerp(self.b, other.b),
+            a: lerp(self.a, other.a),
+        }
+    }
+
+    // ---------- 对比度 ----------
+
+    // WCAG 2.x 相对亮度，忽略 alpha
+    pub fn relative_luminance(self) -> f64 {
+        let linear = |c: f64| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+        let [r, g, b] = self.channels().map(linear);
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    // 1.0 ~ 21.0
+    pub fn contrast_ratio(self, other: Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    // ---------- 终端 ----------
+
+    // 16~255 范围内最接近的 ANSI 颜色编号（0~15 随终端主题变化，不参与匹配）
+    pub fn nearest_ansi256(self) -> u8 {
+        let nearest_level = |c: u8| {
+            (0..6)
+                .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+                .unwrap_or(0)
+        };
+        let (ri, gi, bi) = (nearest_level(self.r), nearest_level(self.g), nearest_level(self.b));
+        let cube_index = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+        let average = (self.r as u32 + self.g as u32 + self.b as u32) / 3;
+        let gray_step = ((average.saturating_sub(8) + 5) / 10).min(23) as u8;
+        let gray_index = 232 + gray_step;
+
+        let distance = |index: u8| {
+            let c = Color::from_ansi256(index);
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.r, self.r) + d(c.g, self.g) + d(c.b, self.b)
+        };
+        if distance(gray_index) < distance(cube_index) { gray_index } else { cube_index }
+    }
+
+    // ANSI 256 色编号对应的 RGB（0~15 使用 xterm 默认值）
+    pub fn from_ansi256(index: u8) -> Color {
+        const SYSTEM: [(u8, u8, u8); 16] = [
+            (0, 0, 0), (128, 0, 0), (0, 128, 0), (128, 128, 0),
+            (0, 0, 128), (128, 0, 128), (0, 128, 128), (192, 192, 192),
+            (128, 128, 128), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+            (0, 0, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+        ];
+        match index {
+            0..=15 => {
+                let (r, g, b) = SYSTEM[index as usize];
+                Color::rgb(r, g, b)
+            }
+            16..=231 => {
+                let i = (index - 16) as usize;
+                Color::rgb(CUBE_LEVELS[i / 36], CUBE_LEVELS[i / 6 % 6], CUBE_LEVELS[i % 6])
+            }
+            _ => {
+                let v = 8 + (index - 232) * 10;
+                Color::rgb(v, v, v)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseColorError(s.to_string());
+        let text = s.trim().to_ascii_lowercase();
+
+        if let Some(hex) = text.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(err());
+            }
+            let nibble = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map(|v| v * 17);
+            let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+            let parsed = match hex.len() {
+                3 => (nibble(0), nibble(1), nibble(2), Ok(255)),
+                4 => (nibble(0), nibble(1), nibble(2), nibble(3)),
+                6 => (byte(0), byte(2), byte(4), Ok(255)),
+                8 => (byte(0), byte(2), byte(4), byte(6)),
+                _ => return Err(err()),
+            };
+            return match parsed {
+                (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Color { r, g, b, a }),
+                _ => Err(err()),
+            };
+        }
+
+        // rgb(1, 2, 3) / rgba(1, 2, 3, 0.5)
+        let (args, has_alpha) = if let Some(rest) = text.strip_prefix("rgba(") {
+            (rest, true)
+        } else if let Some(rest) = text.strip_prefix("rgb(") {
+            (rest, false)
+        } else {
+            return Err(err());
+        };
+        let args = args.strip_suffix(')').ok_or_else(err)?;
+        let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        let channel = |p: &str| p.parse::<u8>().map_err(|_| err());
+        match (parts.as_slice(), has_alpha) {
+            ([r, g, b], false) => Ok(Color::rgb(channel(r)?, channel(g)?, channel(b)?)),
+            ([r, g, b, a], true) => {
+                let alpha: f64 = a.parse().map_err(|_| err())?;
+                if !(0.0..=1.0).contains(&alpha) {
+                    return Err(err());
+                }
+                Ok(Color::rgba(channel(r)?, channel(g)?, channel(b)?, (alpha * 255.0).round() as u8))
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Color {
+        s.parse().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn parses_hex_and_functional_notation() {
+        assert_eq!(parse("#fff"), Color::WHITE);
+        assert_eq!(parse("#ffffff"), Color::WHITE);
+        assert_eq!(parse("  #FFFFFF "), Color::WHITE);
+        assert_eq!(parse("#1234"), Color::rgba(0x11, 0x22, 0x33, 0x44));
+        assert_eq!(parse("#0a0b0c80"), Color::rgba(10, 11, 12, 128));
+        assert_eq!(parse("rgb(1, 2, 3)"), Color::rgb(1, 2, 3));
+        assert_eq!(parse("RGBA(255,0,0,0.5)"), Color::rgba(255, 0, 0, 128));
+        assert_eq!(parse("rgba(0, 0, 0, 0)"), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for color in [Color::BLACK, Color::rgb(18, 52, 86), Color::rgba(1, 2, 3, 4)] {
+            assert_eq!(parse(&color.to_string()), color);
+        }
+        assert_eq!(Color::rgb(255, 0, 16).to_string(), "#ff0010");
+        assert_eq!(Color::rgba(255, 0, 16, 0).to_string(), "#ff001000");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for bad in [
+            "", "#", "#ff", "#fffff", "#ggg", "#+ff", "fff", "rgb(1,2)", "rgb(1,2,3,4)", "rgb(256,0,0)",
+            "rgb(-1,0,0)", "rgb(1,2,3", "rgba(1,2,3)", "rgba(1,2,3,1.5)", "rgba(1,2,3,nan)", "hsl(0,0,0)",
+        ] {
+            assert!(bad.parse::<Color>().is_err(), "{bad:?} 不应解析成功");
+        }
+    }
+
+    #[test]
+    fn hsl_and_hsv_known_values() {
+        assert_eq!(Color::from_hsl(Hsl { h: 0.0, s: 1.0, l: 0.5 }), Color::rgb(255, 0, 0));
+        assert_eq!(Color::from_hsl(Hsl { h: 120.0, s: 1.0, l: 0.25 }), Color::rgb(0, 128, 0));
+        assert_eq!(Color::from_hsv(Hsv { h: 240.0, s: 1.0, v: 1.0 }), Color::rgb(0, 0, 255));
+        let hsl = Color::rgb(255, 255, 0).to_hsl();
+        assert_eq!((hsl.h, hsl.s, hsl.l), (60.0, 1.0, 0.5));
+        let hsv = Color::rgb(128, 128, 128).to_hsv();
+        assert_eq!(hsv.s, 0.0);
+    }
+
+    #[test]
+    fn hsl_rgb_round_trip() {
+        // HSL -> RGB -> HSL: 误差不超过一个 u8 的量化步长
+        for h in (0..360).step_by(15) {
+            for s in [0.25, 0.5, 1.0] {
+                for l in [0.25, 0.5, 0.75] {
+                    let back = Color::from_hsl(Hsl { h: h as f64, s, l }).to_hsl();
+                    assert!((back.h - h as f64).abs() < 1.5, "h {h} -> {}", back.h);
+                    assert!((back.s - s).abs() < 0.02 && (back.l - l).abs() < 0.01, "{h} {s} {l} -> {back:?}");
+                }
+            }
+        }
+        // RGB -> HSL / HSV -> RGB 精确还原
+        for r in (0..=255).step_by(17) {
+            for g in (0..=255).step_by(51) {
+                for b in (0..=255).step_by(85) {
+                    let color = Color::rgb(r, g, b);
+                    assert_eq!(Color::from_hsl(color.to_hsl()), color);
+                    assert_eq!(Color::from_hsv(color.to_hsv()), color);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blend_and_mix() {
+        let half_red = Color::rgba(255, 0, 0, 128);
+        assert_eq!(half_red.blend(Color::WHITE), Color::rgb(255, 127, 127));
+        assert_eq!(Color::WHITE.blend(half_red), Color::WHITE);
+        assert_eq!(Color::TRANSPARENT.blend(Color::TRANSPARENT), Color::TRANSPARENT);
+        assert_eq!(Color::BLACK.mix(Color::WHITE, 0.5), Color::rgb(128, 128, 128));
+        assert_eq!(Color::BLACK.mix(Color::WHITE, 2.0), Color::WHITE);
+    }
+
+    #[test]
+    fn wcag_contrast() {
+        assert_eq!(Color::BLACK.contrast_ratio(Color::WHITE), 21.0);
+        assert_eq!(Color::WHITE.contrast_ratio(Color::BLACK), 21.0);
+        assert_eq!(Color::rgb(1, 2, 3).contrast_ratio(Color::rgb(1, 2, 3)), 1.0);
+        // #767676 是白底上刚好达到 4.5:1 的灰
+        assert!(Color::rgb(0x76, 0x76, 0x76).contrast_ratio(Color::WHITE) >= 4.5);
+        assert!(Color::rgb(0x77, 0x77, 0x77).contrast_ratio(Color::WHITE) < 4.5);
+    }
+
+    #[test]
+    fn nearest_ansi256_picks_cube_or_grayscale() {
+        assert_eq!(Color::BLACK.nearest_ansi256(), 16);
+        assert_eq!(Color::WHITE.nearest_ansi256(), 231);
+        assert_eq!(Color::rgb(255, 0, 0).nearest_ansi256(), 196);
+        assert_eq!(Color::rgb(95, 135, 175).nearest_ansi256(), 67);
+        // 中灰落在两个色块级别之间，灰度条更接近
+        assert_eq!(Color::rgb(128, 128, 128).nearest_ansi256(), 244);
+        assert_eq!(Color::rgb(238, 238, 238).nearest_ansi256(), 255);
+        for index in 16..=255u8 {
+            let color = Color::from_ansi256(index);
+            assert_eq!(Color::from_ansi256(color.nearest_ansi256()), color, "#{index}");
+        }
+    }
+}
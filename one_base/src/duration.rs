@@ -0,0 +1,318 @@
+// ---------------- 时长 ----------------
+/*
+    what: 人类可读的时长，替代 60 * 60 * 3 这类手算常量和各自发明的配置格式。
+    how:
+        - 解析: 3h、1h30m、90s、1.5h、500ms、1天2小时、2周，各部分之间可以有空格;
+        - 格式化: Compact (1h30m)、English (1 hour 30 minutes)、
+                  Chinese (1小时30分钟)、ChineseWords (一小时三十分钟);
+        - 精度到纳秒: 不足 1 毫秒的部分输出为 us、ns，Display 的结果能原样解析回来;
+        - 常量: hours(3) 等 const fn，溢出时取 u64 秒数的上限; 编译期解析 parse_const("1h30m");
+        - 配置: HumanDuration 实现 FromStr + Display，可以直接读写配置值;
+*/
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::chinese_numeral::{to_chinese, NumeralStyle};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+// ---------- const fn ----------
+
+pub const fn seconds(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+// 乘法溢出时取上限，不会 panic
+pub const fn minutes(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(60))
+}
+
+pub const fn hours(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(60 * 60))
+}
+
+pub const fn days(n: u64) -> Duration {
+    Duration::from_secs(n.saturating_mul(24 * 60 * 60))
+}
+
+// 编译期解析，仅支持整数和 ms/s/m/h/d/w，格式错误时编译失败:
+// const TIMEOUT: Duration = parse_const("1h30m");
+pub const fn parse_const(s: &str) -> Duration {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    let mut total_ms: u64 = 0;
+    if bytes.is_empty() {
+        panic!("时长为空");
+    }
+    while i < bytes.len() {
+        let start = i;
+        let mut n: u64 = 0;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            n = match n.checked_mul(10) {
+                Some(v) => v + (bytes[i] - b'0') as u64,
+                None => panic!("时长溢出"),
+            };
+            i += 1;
+        }
+        if i == start {
+            panic!("缺少数字");
+        }
+        let unit_ms = if i + 1 < bytes.len() && bytes[i] == b'm' && bytes[i + 1] == b's' {
+            i += 2;
+            1
+        } else if i < bytes.len() {
+            i += 1;
+            match bytes[i - 1] {
+                b's' => 1_000,
+                b'm' => 60_000,
+                b'h' => 3_600_000,
+                b'd' => 86_400_000,
+                b'w' => 604_800_000,
+                _ => panic!("未知的时间单位"),
+            }
+        } else {
+            panic!("缺少时间单位");
+        };
+        total_ms = match n.checked_mul(unit_ms) {
+            Some(v) if v <= u64::MAX - total_ms => total_ms + v,
+            _ => panic!("时长溢出"),
+        };
+    }
+    Duration::from_millis(total_ms)
+}
+
+// ---------- 解析 ----------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurationError {
+    Empty,
+    MissingNumber(String),
+    MissingUnit(String),
+    UnknownUnit(String),
+    Overflow,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurationError::Empty => write!(f, "时长为空"),
+            DurationError::MissingNumber(at) => write!(f, "缺少数字: {at}"),
+            DurationError::MissingUnit(n) => write!(f, "数字 {n} 缺少时间单位"),
+            DurationError::UnknownUnit(u) => write!(f, "未知的时间单位: {u}"),
+            DurationError::Overflow => write!(f, "时长溢出"),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+fn unit_nanos(unit: &str) -> Option<u128> {
+    let secs: u128 = match unit.to_ascii_lowercase().as_str() {
+        "ns" | "nanosecond" | "nanoseconds" | "纳秒" => return Some(1),
+        "us" | "µs" | "microsecond" | "microseconds" | "微秒" => return Some(1_000),
+        "ms" | "millisecond" | "milliseconds" | "毫秒" => return Some(1_000_000),
+        "s" | "sec" | "secs" | "second" | "seconds" | "秒" | "秒钟" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" | "分" | "分钟" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" | "时" | "小时" | "个小时" => 3_600,
+        "d" | "day" | "days" | "天" | "日" => 86_400,
+        "w" | "week" | "weeks" | "周" | "星期" | "个星期" => 604_800,
+        _ => return None,
+    };
+    Some(secs * NANOS_PER_SEC)
+}
+
+pub fn parse_duration(s: &str) -> Result<Duration, DurationError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(DurationError::Empty);
+    }
+    let mut total: u128 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        // 数字部分: 整数 + 可选小数
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        if number.is_empty() {
+            return Err(DurationError::MissingNumber(rest.to_string()));
+        }
+        let tail = tail.trim_start();
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        if unit.is_empty() {
+            return Err(DurationError::MissingUnit(number.to_string()));
+        }
+        let unit = unit_nanos(unit).ok_or_else(|| DurationError::UnknownUnit(unit.to_string()))?;
+        total = total.checked_add(scaled(number, unit)?).ok_or(DurationError::Overflow)?;
+        rest = tail.trim_start();
+    }
+    let secs = u64::try_from(total / NANOS_PER_SEC).map_err(|_| DurationError::Overflow)?;
+    Ok(Duration::new(secs, (total % NANOS_PER_SEC) as u32))
+}
+
+// "1.5" × unit，全程整数运算，小数最多保留 9 位
+fn scaled(number: &str, unit: u128) -> Result<u128, DurationError> {
+    let bad = || DurationError::MissingNumber(number.to_string());
+    let (int_part, frac_part) = number.split_once('.').unwrap_or((number, ""));
+    if int_part.is_empty() && frac_part.is_empty() || frac_part.contains('.') {
+        return Err(bad());
+    }
+    let int: u128 = if int_part.is_empty() { 0 } else { int_part.parse().map_err(|_| DurationError::Overflow)? };
+    let frac_part = &frac_part[..frac_part.len().min(9)];
+    let frac: u128 = if frac_part.is_empty() { 0 } else { frac_part.parse().map_err(|_| bad())? };
+    let frac_nanos = frac * unit / 10u128.pow(frac_part.len() as u32);
+    int.checked_mul(unit)
+        .and_then(|v| v.checked_add(frac_nanos))
+        .ok_or(DurationError::Overflow)
+}
+
+// ---------- 格式化 ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationStyle {
+    #[default]
+    Compact,
+    English,
+    Chinese,
+    ChineseWords,
+}
+
+// (天, 时, 分, 秒, 毫秒, 微秒, 纳秒)
+fn components(d: Duration) -> [(u64, usize); 7] {
+    let secs = d.as_secs();
+    let nanos = d.subsec_nanos() as u64;
+    [
+        (secs / 86_400, 0),
+        (secs / 3_600 % 24, 1),
+        (secs / 60 % 60, 2),
+        (secs % 60, 3),
+        (nanos / 1_000_000, 4),
+        (nanos / 1_000 % 1_000, 5),
+        (nanos % 1_000, 6),
+    ]
+}
+
+const COMPACT: [&str; 7] = ["d", "h", "m", "s", "ms", "us", "ns"];
+const ENGLISH: [(&str, &str); 7] = [
+    ("day", "days"),
+    ("hour", "hours"),
+    ("minute", "minutes"),
+    ("second", "seconds"),
+    ("millisecond", "milliseconds"),
+    ("microsecond", "microseconds"),
+    ("nanosecond", "nanoseconds"),
+];
+const CHINESE: [&str; 7] = ["天", "小时", "分钟", "秒", "毫秒", "微秒", "纳秒"];
+
+pub fn format_duration(d: Duration, style: DurationStyle) -> String {
+    let parts: Vec<(u64, usize)> = components(d).into_iter().filter(|(n, _)| *n > 0).collect();
+    if parts.is_empty() {
+        return match style {
+            DurationStyle::Compact => "0s".to_string(),
+            DurationStyle::English => "0 seconds".to_string(),
+            DurationStyle::Chinese => "0秒".to_string(),
+            DurationStyle::ChineseWords => "零秒".to_string(),
+        };
+    }
+    let words: Vec<String> = parts
+        .into_iter()
+        .map(|(n, unit)| match style {
+            DurationStyle::Compact => format!("{n}{}", COMPACT[unit]),
+            DurationStyle::English => {
+                let (one, many) = ENGLISH[unit];
+                format!("{n} {}", if n == 1 { one } else { many })
+            }
+            DurationStyle::Chinese => format!("{n}{}", CHINESE[unit]),
+            // 量词前的 2 读作 两
+            DurationStyle::ChineseWords if n == 2 => format!("两{}", CHINESE[unit]),
+            DurationStyle::ChineseWords => format!("{}{}", to_chinese(n, NumeralStyle::Simple), CHINESE[unit]),
+        })
+        .collect();
+    let separator = if style == DurationStyle::English { " " } else { "" };
+    words.join(separator)
+}
+
+// 配置文件中的时长字段: "30s".parse::<HumanDuration>()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl FromStr for HumanDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(HumanDuration)
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_duration(self.0, DurationStyle::Compact))
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(d: HumanDuration) -> Duration {
+        d.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [Duration; 8] = [
+        Duration::ZERO,
+        Duration::from_nanos(1),
+        Duration::from_micros(500),
+        Duration::from_millis(1_500),
+        Duration::new(5_400, 250_000_001),
+        Duration::from_secs(90_061),
+        Duration::from_secs(14 * 86_400),
+        Duration::MAX,
+    ];
+
+    #[test]
+    fn display_round_trips() {
+        for d in SAMPLES {
+            let text = HumanDuration(d).to_string();
+            assert_eq!(text.parse::<HumanDuration>(), Ok(HumanDuration(d)), "{text}");
+        }
+        assert_eq!("500us".parse::<HumanDuration>().unwrap().to_string(), "500us");
+        assert_eq!(HumanDuration(Duration::new(5_400, 250_000_001)).to_string(), "1h30m250ms1ns");
+    }
+
+    #[test]
+    fn english_and_chinese_round_trip() {
+        for d in SAMPLES {
+            for style in [DurationStyle::English, DurationStyle::Chinese] {
+                let text = format_duration(d, style);
+                assert_eq!(parse_duration(&text), Ok(d), "{text}");
+            }
+        }
+        assert_eq!(format_duration(Duration::from_micros(1), DurationStyle::English), "1 microsecond");
+        assert_eq!(format_duration(Duration::from_nanos(2), DurationStyle::ChineseWords), "两纳秒");
+    }
+
+    #[test]
+    fn parse_forms() {
+        assert_eq!(parse_duration("1.5h"), Ok(hours(1) + minutes(30)));
+        assert_eq!(parse_duration("1天 2小时"), Ok(days(1) + hours(2)));
+        assert_eq!(parse_duration("2周"), Ok(days(14)));
+        assert_eq!(parse_duration(""), Err(DurationError::Empty));
+        assert_eq!(parse_duration("10"), Err(DurationError::MissingUnit("10".to_string())));
+        assert_eq!(parse_duration("3 fortnights"), Err(DurationError::UnknownUnit("fortnights".to_string())));
+        assert_eq!(parse_duration("99999999999999999999w"), Err(DurationError::Overflow));
+    }
+
+    #[test]
+    fn const_helpers_saturate() {
+        const TIMEOUT: Duration = parse_const("1h30m");
+        assert_eq!(TIMEOUT, Duration::from_secs(5_400));
+        assert_eq!(hours(u64::MAX), Duration::from_secs(u64::MAX));
+        assert_eq!(days(u64::MAX / 2), Duration::from_secs(u64::MAX));
+        assert_eq!(minutes(2), Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,32 @@
+// ---------------- 模块: 花园模拟 ----------------
+/*
+    课程中的 mod garden { pub fn plant() } 扩展为一个文件模块树:
+
+    garden              garden.rs          入口，pub use 重新导出常用类型
+    ├── species         garden/species.rs  植物种类、生长阶段、单株植物
+    ├── plot            garden/plot.rs     地块网格
+    ├── climate         garden/climate.rs  季节、天气、可复现的随机数
+    ├── simulation      garden/simulation.rs  每日 tick 循环与收获
+    └── storage         garden/storage.rs  存档与读档
+
+    - 文件模块: mod species; 会查找 garden/species.rs（2018 之后无需 mod.rs）;
+    - 可见性: pub 让外部使用; pub(super)/pub(crate) 限制在父模块/本 crate 内;
+    - 路径: 子模块之间用 super::xxx 或 crate::garden::xxx 引用;
+*/
+
+pub mod climate;
+pub mod plot;
+pub mod simulation;
+pub mod species;
+pub mod storage;
+
+// 重新导出: 使用者写 garden::Garden 即可，不必关心内部文件结构
+pub use climate::{Rng, Season, Weather};
+pub use plot::{Cell, Plot};
+pub use simulation::{Garden, GardenError, Harvest};
+pub use species::{Plant, Species, Stage};
+
+// 课程原函数
+pub fn plant() {
+    println!("种植蔬菜!");
+}
@@ -0,0 +1,143 @@
+// 季节、天气与可复现的随机数
+
+use std::fmt;
+
+pub const DAYS_PER_SEASON: u32 = 30;
+
+// SplitMix64: 状态只有一个 u64，便于存档后继续得到相同的序列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [low, high] 闭区间内的整数
+    pub fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + (self.next_u64() % (high - low + 1) as u64) as u32
+    }
+
+    // 以 percent% 的概率返回 true
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.range(1, 100) <= percent
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    // 第 day 天所处的季节（day 从 0 开始，每季 30 天）
+    pub fn of_day(day: u32) -> Season {
+        match (day / DAYS_PER_SEASON) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    // (日照小时区间, 降雨概率%, 每日蒸发量)
+    fn profile(self) -> ((u32, u32), u32, u8) {
+        match self {
+            Season::Spring => ((5, 9), 40, 4),
+            Season::Summer => ((7, 12), 20, 8),
+            Season::Autumn => ((4, 8), 30, 4),
+            Season::Winter => ((1, 5), 25, 2),
+        }
+    }
+
+    pub fn weather(self, rng: &mut Rng) -> Weather {
+        let ((sun_low, sun_high), rain_chance, evaporation) = self.profile();
+        let sunlight = rng.range(sun_low, sun_high) as u8;
+        let rainfall = if rng.chance(rain_chance) { rng.range(5, 30) as u8 } else { 0 };
+        Weather { season: self, sunlight, rainfall, evaporation }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Season::Spring => "春",
+            Season::Summer => "夏",
+            Season::Autumn => "秋",
+            Season::Winter => "冬",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weather {
+    pub season: Season,
+    pub sunlight: u8,
+    // 降雨增加的土壤湿度
+    pub rainfall: u8,
+    pub evaporation: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seasons_cycle_every_thirty_days() {
+        assert_eq!(Season::of_day(0), Season::Spring);
+        assert_eq!(Season::of_day(29), Season::Spring);
+        assert_eq!(Season::of_day(30), Season::Summer);
+        assert_eq!(Season::of_day(60), Season::Autumn);
+        assert_eq!(Season::of_day(119), Season::Winter);
+        assert_eq!(Season::of_day(120), Season::Spring);
+    }
+
+    #[test]
+    fn rng_is_reproducible_and_in_range() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        for _ in 0..1_000 {
+            let v = a.range(3, 9);
+            assert_eq!(v, b.range(3, 9));
+            assert!((3..=9).contains(&v));
+        }
+        assert_eq!(a, b);
+        assert!(!Rng::new(1).chance(0));
+        assert!(Rng::new(1).chance(100));
+    }
+
+    #[test]
+    fn weather_follows_the_season_profile() {
+        let mut rng = Rng::new(42);
+        for season in [Season::Spring, Season::Summer, Season::Autumn, Season::Winter] {
+            let ((sun_low, sun_high), _, evaporation) = season.profile();
+            let mut rainy = 0;
+            for _ in 0..500 {
+                let weather = season.weather(&mut rng);
+                assert_eq!(weather.season, season);
+                assert!((sun_low..=sun_high).contains(&(weather.sunlight as u32)), "{season}: {weather:?}");
+                assert!(weather.rainfall == 0 || (5..=30).contains(&weather.rainfall));
+                assert_eq!(weather.evaporation, evaporation);
+                rainy += (weather.rainfall > 0) as u32;
+            }
+            assert!(rainy > 0 && rainy < 500, "{season} 下雨 {rainy} 天");
+        }
+    }
+}
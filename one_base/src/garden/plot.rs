@@ -0,0 +1,125 @@
+// 地块网格: width × height 个格子，按行存储在 Vec 中
+
+use super::simulation::GardenError;
+use super::species::Plant;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    // 土壤湿度 0~100
+    pub moisture: u8,
+    pub plant: Option<Plant>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell { moisture: 50, plant: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plot {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Plot {
+    // 宽高为 0 或格子总数溢出时返回 InvalidSize
+    pub fn new(width: usize, height: usize) -> Result<Self, GardenError> {
+        let count = width.checked_mul(height).filter(|&n| n > 0).ok_or(GardenError::InvalidSize { width, height })?;
+        Ok(Plot { width, height, cells: vec![Cell::default(); count] })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        self.index(x, y).map(|i| &mut self.cells[i])
+    }
+
+    // 按 (x, y, 格子) 遍历
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, c)| (i % width, i / width, c))
+    }
+
+    pub(super) fn cells_mut(&mut self) -> impl Iterator<Item = &mut Cell> {
+        self.cells.iter_mut()
+    }
+
+    // 文本网格: . 空地，小写为未成熟，大写为成熟，x 为枯萎
+    pub fn render(&self) -> String {
+        use super::species::Stage;
+
+        let mut out = String::new();
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                let ch = match &cell.plant {
+                    None => '.',
+                    Some(p) if p.stage == Stage::Withered => 'x',
+                    Some(p) if p.stage == Stage::Mature => p.species.symbol(),
+                    Some(p) => p.species.symbol().to_ascii_lowercase(),
+                };
+                out.push(ch);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::garden::species::{Species, Stage};
+
+    #[test]
+    fn capacity_and_bounds() {
+        let mut plot = Plot::new(3, 2).unwrap();
+        assert_eq!((plot.width(), plot.height()), (3, 2));
+        assert_eq!(plot.iter().count(), 6);
+        assert!(plot.get(2, 1).is_some());
+        assert!(plot.get(3, 0).is_none());
+        assert!(plot.get(0, 2).is_none());
+        assert!(plot.get_mut(usize::MAX, usize::MAX).is_none());
+        assert_eq!(Plot::new(4, 0), Err(GardenError::InvalidSize { width: 4, height: 0 }));
+        assert_eq!(Plot::new(usize::MAX, 2), Err(GardenError::InvalidSize { width: usize::MAX, height: 2 }));
+    }
+
+    #[test]
+    fn cells_are_stored_row_by_row() {
+        let mut plot = Plot::new(3, 2).unwrap();
+        plot.get_mut(2, 0).unwrap().moisture = 7;
+        plot.get_mut(0, 1).unwrap().moisture = 9;
+        let coords: Vec<(usize, usize, u8)> = plot.iter().map(|(x, y, c)| (x, y, c.moisture)).collect();
+        assert_eq!(coords[2], (2, 0, 7));
+        assert_eq!(coords[3], (0, 1, 9));
+        assert!(coords.iter().all(|&(_, _, m)| m == 50 || m == 7 || m == 9));
+    }
+
+    #[test]
+    fn render_marks_each_stage() {
+        let mut plot = Plot::new(2, 2).unwrap();
+        let mut mature = Plant::new(Species::Tomato);
+        mature.stage = Stage::Mature;
+        let mut withered = Plant::new(Species::Corn);
+        withered.stage = Stage::Withered;
+        plot.get_mut(0, 0).unwrap().plant = Some(mature);
+        plot.get_mut(1, 0).unwrap().plant = Some(Plant::new(Species::Carrot));
+        plot.get_mut(1, 1).unwrap().plant = Some(withered);
+        assert_eq!(plot.render(), "Tc\n.x\n");
+    }
+}
@@ -0,0 +1,254 @@
+// 模拟循环: 每次 tick 代表一天
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::climate::{Rng, Season, Weather};
+use super::plot::Plot;
+use super::species::{Plant, Species, Stage};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GardenError {
+    OutOfBounds { x: usize, y: usize },
+    Occupied { x: usize, y: usize },
+    Empty { x: usize, y: usize },
+    NotMature { x: usize, y: usize },
+    InvalidSize { width: usize, height: usize },
+    Corrupt { line: usize, reason: String },
+}
+
+impl fmt::Display for GardenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GardenError::OutOfBounds { x, y } => write!(f, "({x}, {y}) 超出地块范围"),
+            GardenError::Occupied { x, y } => write!(f, "({x}, {y}) 已经种了植物"),
+            GardenError::Empty { x, y } => write!(f, "({x}, {y}) 没有植物"),
+            GardenError::NotMature { x, y } => write!(f, "({x}, {y}) 的植物尚未成熟"),
+            GardenError::InvalidSize { width, height } => {
+                write!(f, "地块尺寸 {width}×{height} 无效: 宽高都要大于 0，格子总数不能溢出")
+            }
+            GardenError::Corrupt { line, reason } => write!(f, "存档第 {line} 行损坏: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for GardenError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Harvest {
+    pub species: Species,
+    pub amount: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garden {
+    pub(super) plot: Plot,
+    pub(super) rng: Rng,
+    pub(super) day: u32,
+    // 按种类累计的收获量
+    pub(super) harvested: BTreeMap<Species, u32>,
+}
+
+impl Garden {
+    // 相同的 seed 和操作序列得到相同的结果
+    pub fn new(width: usize, height: usize, seed: u64) -> Result<Self, GardenError> {
+        Ok(Garden { plot: Plot::new(width, height)?, rng: Rng::new(seed), day: 0, harvested: BTreeMap::new() })
+    }
+
+    pub fn plot(&self) -> &Plot {
+        &self.plot
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn season(&self) -> Season {
+        Season::of_day(self.day)
+    }
+
+    pub fn harvested(&self) -> &BTreeMap<Species, u32> {
+        &self.harvested
+    }
+
+    pub fn sow(&mut self, x: usize, y: usize, species: Species) -> Result<(), GardenError> {
+        let cell = self.plot.get_mut(x, y).ok_or(GardenError::OutOfBounds { x, y })?;
+        if cell.plant.is_some() {
+            return Err(GardenError::Occupied { x, y });
+        }
+        cell.plant = Some(Plant::new(species));
+        Ok(())
+    }
+
+    pub fn water(&mut self, x: usize, y: usize, amount: u8) -> Result<(), GardenError> {
+        let cell = self.plot.get_mut(x, y).ok_or(GardenError::OutOfBounds { x, y })?;
+        cell.moisture = cell.moisture.saturating_add(amount).min(100);
+        Ok(())
+    }
+
+    // 给所有种了植物的格子浇水
+    pub fn water_all(&mut self, amount: u8) {
+        for cell in self.plot.cells_mut().filter(|c| c.plant.is_some()) {
+            cell.moisture = cell.moisture.saturating_add(amount).min(100);
+        }
+    }
+
+    // 照料: 把偏干的格子浇到该植物适宜湿度区间的中点
+    pub fn tend(&mut self) {
+        for cell in self.plot.cells_mut() {
+            if let Some(plant) = &cell.plant {
+                let (low, high) = plant.species.needs().moisture;
+                cell.moisture = cell.moisture.max(low + (high - low) / 2);
+            }
+        }
+    }
+
+    // 推进一天，返回当天天气
+    pub fn tick(&mut self) -> Weather {
+        let weather = self.season().weather(&mut self.rng);
+        crate::trace!(day = self.day, rainfall = weather.rainfall, sunlight = weather.sunlight; "新的一天");
+        for cell in self.plot.cells_mut() {
+            cell.moisture = cell
+                .moisture
+                .saturating_add(weather.rainfall)
+                .saturating_sub(weather.evaporation)
+                .min(100);
+            if let Some(plant) = cell.plant.as_mut() {
+                let before = plant.stage;
+                plant.grow(cell.moisture, weather.sunlight);
+                if plant.stage != before && plant.stage == Stage::Withered {
+                    crate::debug!(species = plant.species, moisture = cell.moisture; "植物枯萎");
+                }
+            }
+        }
+        self.day += 1;
+        weather
+    }
+
+    pub fn run(&mut self, days: u32) {
+        for _ in 0..days {
+            self.tick();
+        }
+    }
+
+    pub fn harvest(&mut self, x: usize, y: usize) -> Result<Harvest, GardenError> {
+        let cell = self.plot.get_mut(x, y).ok_or(GardenError::OutOfBounds { x, y })?;
+        let plant = cell.plant.as_ref().ok_or(GardenError::Empty { x, y })?;
+        let amount = plant.harvest_yield().ok_or(GardenError::NotMature { x, y })?;
+        let species = plant.species;
+        cell.plant = None;
+        *self.harvested.entry(species).or_insert(0) += amount;
+        Ok(Harvest { species, amount })
+    }
+
+    // 收获所有成熟植物，并清理枯萎的植物
+    pub fn harvest_all(&mut self) -> Vec<Harvest> {
+        let mut ripe = Vec::new();
+        for (x, y, cell) in self.plot.iter() {
+            if cell.plant.as_ref().and_then(Plant::harvest_yield).is_some() {
+                ripe.push((x, y));
+            }
+        }
+        for cell in self.plot.cells_mut() {
+            if cell.plant.as_ref().is_some_and(|p| p.stage == Stage::Withered) {
+                cell.plant = None;
+            }
+        }
+        ripe.into_iter().filter_map(|(x, y)| self.harvest(x, y).ok()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plant(garden: &Garden, x: usize, y: usize) -> &Plant {
+        garden.plot.get(x, y).and_then(|c| c.plant.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn sow_and_harvest_report_errors() {
+        let mut garden = Garden::new(2, 1, 1).unwrap();
+        assert_eq!(garden.sow(2, 0, Species::Carrot), Err(GardenError::OutOfBounds { x: 2, y: 0 }));
+        garden.sow(0, 0, Species::Carrot).unwrap();
+        assert_eq!(garden.sow(0, 0, Species::Corn), Err(GardenError::Occupied { x: 0, y: 0 }));
+        assert_eq!(garden.harvest(1, 0), Err(GardenError::Empty { x: 1, y: 0 }));
+        assert_eq!(garden.harvest(0, 0), Err(GardenError::NotMature { x: 0, y: 0 }));
+        assert_eq!(garden.water(0, 1, 10), Err(GardenError::OutOfBounds { x: 0, y: 1 }));
+        garden.water(0, 0, 200).unwrap();
+        assert_eq!(garden.plot.get(0, 0).unwrap().moisture, 100);
+    }
+
+    #[test]
+    fn tick_applies_rain_and_evaporation() {
+        let mut garden = Garden::new(1, 1, 3).unwrap();
+        garden.day = 30;
+        for _ in 0..30 {
+            let before = garden.plot.get(0, 0).unwrap().moisture;
+            let weather = garden.tick();
+            assert_eq!(weather.season, Season::Summer);
+            let expected = (before + weather.rainfall).saturating_sub(weather.evaporation).min(100);
+            assert_eq!(garden.plot.get(0, 0).unwrap().moisture, expected);
+        }
+        assert_eq!((garden.day(), garden.season()), (60, Season::Autumn));
+    }
+
+    #[test]
+    fn tended_lettuce_matures_in_spring() {
+        let mut garden = Garden::new(1, 1, 11).unwrap();
+        garden.sow(0, 0, Species::Lettuce).unwrap();
+        for _ in 0..30 {
+            garden.tend();
+            garden.tick();
+        }
+        assert_eq!(plant(&garden, 0, 0).stage, Stage::Mature);
+        let harvest = garden.harvest(0, 0).unwrap();
+        assert_eq!(harvest.species, Species::Lettuce);
+        assert!(harvest.amount > 0);
+        assert_eq!(garden.harvested()[&Species::Lettuce], harvest.amount);
+    }
+
+    #[test]
+    fn corn_withers_in_winter_shade() {
+        // 冬天最多 5 小时日照，玉米需要 7 小时: 每天至少扣 4 点健康
+        let mut garden = Garden::new(1, 1, 5).unwrap();
+        garden.day = 90;
+        garden.sow(0, 0, Species::Corn).unwrap();
+        for _ in 0..25 {
+            garden.tend();
+            garden.tick();
+            let corn = plant(&garden, 0, 0);
+            assert!(matches!(corn.stage, Stage::Seed | Stage::Withered) && corn.progress == 0);
+        }
+        assert_eq!(plant(&garden, 0, 0).stage, Stage::Withered);
+    }
+
+    #[test]
+    fn untended_tomatoes_dry_out_in_summer() {
+        let mut garden = Garden::new(3, 1, 7).unwrap();
+        garden.day = 30;
+        for x in 0..3 {
+            garden.sow(x, 0, Species::Tomato).unwrap();
+        }
+        garden.run(30);
+        assert!(garden.plot.iter().all(|(_, _, c)| c.plant.as_ref().unwrap().stage == Stage::Withered));
+        assert_eq!(garden.plot.render(), "xxx\n");
+        // harvest_all 清理枯萎的植物，但不计入收获
+        assert!(garden.harvest_all().is_empty());
+        assert!(garden.plot.iter().all(|(_, _, c)| c.plant.is_none()));
+        assert!(garden.harvested().is_empty());
+    }
+
+    #[test]
+    fn same_seed_same_garden() {
+        let play = |seed| {
+            let mut garden = Garden::new(2, 2, seed).unwrap();
+            garden.sow(0, 0, Species::Tomato).unwrap();
+            garden.sow(1, 1, Species::Carrot).unwrap();
+            garden.run(40);
+            garden
+        };
+        assert_eq!(play(9), play(9));
+        assert_ne!(play(9).rng, play(10).rng);
+    }
+}
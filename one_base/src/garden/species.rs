@@ -0,0 +1,246 @@
+// 植物种类与生长阶段
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Species {
+    Tomato,
+    Carrot,
+    Lettuce,
+    Corn,
+}
+
+// 每个种类的生长参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Needs {
+    // 适宜土壤湿度区间 0~100
+    pub moisture: (u8, u8),
+    // 每天至少需要的日照小时数
+    pub sunlight: u8,
+    // 每个阶段需要的有效生长天数
+    pub days_per_stage: u8,
+    // 满健康度时的收获量
+    pub yield_units: u32,
+}
+
+impl Species {
+    pub const ALL: [Species; 4] = [Species::Tomato, Species::Carrot, Species::Lettuce, Species::Corn];
+
+    pub fn needs(self) -> Needs {
+        match self {
+            Species::Tomato => Needs { moisture: (50, 80), sunlight: 6, days_per_stage: 8, yield_units: 12 },
+            Species::Carrot => Needs { moisture: (40, 70), sunlight: 4, days_per_stage: 6, yield_units: 8 },
+            Species::Lettuce => Needs { moisture: (55, 85), sunlight: 3, days_per_stage: 4, yield_units: 4 },
+            Species::Corn => Needs { moisture: (45, 75), sunlight: 7, days_per_stage: 10, yield_units: 20 },
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Species::Tomato => "番茄",
+            Species::Carrot => "胡萝卜",
+            Species::Lettuce => "生菜",
+            Species::Corn => "玉米",
+        }
+    }
+
+    // 网格显示用的单个字符
+    pub fn symbol(self) -> char {
+        match self {
+            Species::Tomato => 'T',
+            Species::Carrot => 'C',
+            Species::Lettuce => 'L',
+            Species::Corn => 'K',
+        }
+    }
+}
+
+impl fmt::Display for Species {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Species {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Species::ALL
+            .into_iter()
+            .find(|sp| sp.name() == s || format!("{sp:?}").eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("未知的植物: {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Seed,
+    Sprout,
+    Growing,
+    Mature,
+    Withered,
+}
+
+impl Stage {
+    // 下一个生长阶段，成熟和枯萎后不再变化
+    pub fn next(self) -> Stage {
+        match self {
+            Stage::Seed => Stage::Sprout,
+            Stage::Sprout => Stage::Growing,
+            Stage::Growing => Stage::Mature,
+            Stage::Mature | Stage::Withered => self,
+        }
+    }
+}
+
+impl FromStr for Stage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Seed" => Ok(Stage::Seed),
+            "Sprout" => Ok(Stage::Sprout),
+            "Growing" => Ok(Stage::Growing),
+            "Mature" => Ok(Stage::Mature),
+            "Withered" => Ok(Stage::Withered),
+            other => Err(format!("未知的生长阶段: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plant {
+    pub species: Species,
+    pub stage: Stage,
+    // 当前阶段已累计的有效生长天数
+    pub progress: u8,
+    // 健康度 0~100，降到 0 时枯萎
+    pub health: u8,
+    pub age_days: u32,
+}
+
+impl Plant {
+    pub fn new(species: Species) -> Self {
+        Plant { species, stage: Stage::Seed, progress: 0, health: 100, age_days: 0 }
+    }
+
+    // 过一天: 水分和日照都满足则生长，否则损失健康度
+    pub(super) fn grow(&mut self, moisture: u8, sunlight: u8) {
+        if self.stage == Stage::Withered {
+            return;
+        }
+        self.age_days += 1;
+        let needs = self.species.needs();
+        let (low, high) = needs.moisture;
+        let mut stress = 0u8;
+        if moisture < low {
+            stress += (low - moisture).min(20);
+        } else if moisture > high {
+            stress += ((moisture - high) / 5).min(4);
+        }
+        if sunlight < needs.sunlight {
+            stress += (needs.sunlight - sunlight) * 2;
+        }
+
+        if stress == 0 {
+            self.health = (self.health + 2).min(100);
+            if self.stage < Stage::Mature {
+                self.progress += 1;
+                if self.progress >= needs.days_per_stage {
+                    self.stage = self.stage.next();
+                    self.progress = 0;
+                }
+            }
+        } else {
+            self.health = self.health.saturating_sub(stress);
+        }
+        if self.health == 0 {
+            self.stage = Stage::Withered;
+        }
+    }
+
+    // 成熟后才能收获，收获量按健康度折算
+    pub fn harvest_yield(&self) -> Option<u32> {
+        (self.stage == Stage::Mature).then(|| self.species.needs().yield_units * self.health as u32 / 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn species_lookup_by_name() {
+        assert_eq!("番茄".parse(), Ok(Species::Tomato));
+        assert_eq!("carrot".parse(), Ok(Species::Carrot));
+        assert_eq!("LETTUCE".parse(), Ok(Species::Lettuce));
+        for species in Species::ALL {
+            assert_eq!(species.name().parse(), Ok(species));
+            assert_eq!(format!("{species:?}").parse(), Ok(species));
+        }
+        assert_eq!("potato".parse::<Species>(), Err("未知的植物: potato".to_string()));
+        assert!("".parse::<Species>().is_err());
+        // 网格符号互不相同
+        let mut symbols: Vec<char> = Species::ALL.iter().map(|s| s.symbol()).collect();
+        symbols.dedup();
+        assert_eq!(symbols.len(), Species::ALL.len());
+    }
+
+    #[test]
+    fn stages_advance_and_stop() {
+        assert_eq!(Stage::Seed.next(), Stage::Sprout);
+        assert_eq!(Stage::Growing.next(), Stage::Mature);
+        assert_eq!(Stage::Mature.next(), Stage::Mature);
+        assert_eq!(Stage::Withered.next(), Stage::Withered);
+        assert_eq!("Growing".parse(), Ok(Stage::Growing));
+        assert!("growing".parse::<Stage>().is_err());
+    }
+
+    #[test]
+    fn ideal_conditions_grow_to_maturity() {
+        let mut plant = Plant::new(Species::Lettuce);
+        let needs = Species::Lettuce.needs();
+        for _ in 0..needs.days_per_stage as u32 * 3 {
+            plant.grow(70, needs.sunlight);
+        }
+        assert_eq!(plant.stage, Stage::Mature);
+        assert_eq!(plant.health, 100);
+        assert_eq!(plant.harvest_yield(), Some(needs.yield_units));
+        // 成熟后不再前进
+        plant.grow(70, needs.sunlight);
+        assert_eq!((plant.stage, plant.progress), (Stage::Mature, 0));
+    }
+
+    #[test]
+    fn stress_costs_health_and_withers() {
+        let mut plant = Plant::new(Species::Tomato);
+        // 湿度低于下限 50: 每天最多扣 20
+        plant.grow(10, 12);
+        assert_eq!((plant.health, plant.progress), (80, 0));
+        // 过湿: 每超 5 点扣 1，最多 4
+        plant.grow(100, 12);
+        assert_eq!(plant.health, 76);
+        // 日照不足: 每差 1 小时扣 2
+        plant.grow(65, 3);
+        assert_eq!(plant.health, 70);
+        for _ in 0..4 {
+            plant.grow(0, 0);
+        }
+        assert_eq!((plant.stage, plant.health), (Stage::Withered, 0));
+        assert_eq!(plant.harvest_yield(), None);
+        let age = plant.age_days;
+        plant.grow(65, 12);
+        assert_eq!((plant.stage, plant.age_days), (Stage::Withered, age));
+    }
+
+    #[test]
+    fn yield_scales_with_health() {
+        let mut plant = Plant::new(Species::Corn);
+        plant.stage = Stage::Mature;
+        plant.health = 50;
+        assert_eq!(plant.harvest_yield(), Some(10));
+        plant.stage = Stage::Growing;
+        assert_eq!(plant.harvest_yield(), None);
+    }
+}
@@ -0,0 +1,168 @@
+// 存档与读档
+/*
+    文本格式，每行一条记录:
+        garden v1
+        size <宽> <高>
+        day <天数>
+        rng <随机数状态>
+        harvested <种类> <数量>
+        cell <x> <y> <湿度> [<种类> <阶段> <进度> <健康度> <天数>]
+    size 只能出现一次且在所有 cell 之前; 进度必须小于该种类每阶段的天数。
+    随机数状态一起保存，读档后继续模拟与不中断时结果一致。
+*/
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::climate::Rng;
+use super::plot::Plot;
+use super::simulation::{Garden, GardenError};
+use super::species::{Plant, Species};
+
+const HEADER: &str = "garden v1";
+
+impl Garden {
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        // 写入 String 不会失败
+        let _ = writeln!(out, "{HEADER}");
+        let _ = writeln!(out, "size {} {}", self.plot.width(), self.plot.height());
+        let _ = writeln!(out, "day {}", self.day);
+        let _ = writeln!(out, "rng {}", self.rng.state());
+        for (species, amount) in &self.harvested {
+            let _ = writeln!(out, "harvested {species:?} {amount}");
+        }
+        for (x, y, cell) in self.plot.iter() {
+            let _ = write!(out, "cell {x} {y} {}", cell.moisture);
+            if let Some(p) = &cell.plant {
+                let _ = write!(out, " {:?} {:?} {} {} {}", p.species, p.stage, p.progress, p.health, p.age_days);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn from_text(text: &str) -> Result<Garden, GardenError> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            _ => return Err(GardenError::Corrupt { line: 1, reason: format!("缺少文件头 {HEADER}") }),
+        }
+
+        // size 决定地块大小，必须出现在所有 cell 之前且只能出现一次
+        let mut plot: Option<Plot> = None;
+        let mut day = 0;
+        let mut rng = Rng::new(0);
+        let mut harvested = BTreeMap::new();
+        for (i, line) in lines {
+            let err = |reason: String| GardenError::Corrupt { line: i + 1, reason };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let num = |idx: usize| -> Result<u64, GardenError> {
+                let field = fields.get(idx).ok_or_else(|| err(format!("缺少第 {idx} 个字段")))?;
+                field.parse().map_err(|_| err(format!("不是数字: {field}")))
+            };
+            match fields[0] {
+                "size" => {
+                    if plot.is_some() {
+                        return Err(err("size 重复出现".to_string()));
+                    }
+                    let (width, height) = (num(1)? as usize, num(2)? as usize);
+                    plot = Some(Plot::new(width, height).map_err(|e| err(e.to_string()))?);
+                }
+                "day" => day = num(1)? as u32,
+                "rng" => rng = Rng::new(num(1)?),
+                "harvested" => {
+                    let species = fields.get(1).unwrap_or(&"").parse().map_err(err)?;
+                    harvested.insert(species, num(2)? as u32);
+                }
+                "cell" => {
+                    let plot = plot.as_mut().ok_or_else(|| err("cell 出现在 size 之前".to_string()))?;
+                    let (x, y) = (num(1)? as usize, num(2)? as usize);
+                    let moisture = num(3)?.min(100) as u8;
+                    let plant = match fields.get(4) {
+                        None => None,
+                        Some(species) => {
+                            let species: Species = species.parse().map_err(err)?;
+                            // 进度超出本阶段天数时，生长中的 progress += 1 会溢出
+                            let days_per_stage = species.needs().days_per_stage;
+                            let progress = num(6)?;
+                            if progress >= u64::from(days_per_stage) {
+                                return Err(err(format!("进度 {progress} 应小于 {days_per_stage}")));
+                            }
+                            Some(Plant {
+                                species,
+                                stage: fields.get(5).unwrap_or(&"").parse().map_err(err)?,
+                                progress: progress as u8,
+                                health: num(7)?.min(100) as u8,
+                                age_days: num(8)? as u32,
+                            })
+                        }
+                    };
+                    let cell = plot.get_mut(x, y).ok_or_else(|| err(format!("({x}, {y}) 超出地块范围")))?;
+                    cell.moisture = moisture;
+                    cell.plant = plant;
+                }
+                other => return Err(err(format!("未知记录: {other}"))),
+            }
+        }
+        let plot = plot.ok_or_else(|| GardenError::Corrupt { line: 2, reason: "缺少 size 记录".to_string() })?;
+        Ok(Garden { plot, rng, day, harvested })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Garden> {
+        let text = fs::read_to_string(path)?;
+        Garden::from_text(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corrupt_line(text: &str) -> usize {
+        match Garden::from_text(text) {
+            Err(GardenError::Corrupt { line, .. }) => line,
+            other => panic!("应为存档损坏，实际 {other:?}"),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut garden = Garden::new(3, 2, 42).unwrap();
+        garden.sow(1, 1, Species::Tomato).unwrap();
+        for _ in 0..5 {
+            garden.tick();
+        }
+        assert_eq!(Garden::from_text(&garden.to_text()).unwrap(), garden);
+    }
+
+    #[test]
+    fn zero_or_overflowing_size_is_rejected() {
+        assert_eq!(Garden::new(0, 3, 1), Err(GardenError::InvalidSize { width: 0, height: 3 }));
+        assert!(Garden::new(usize::MAX, 2, 1).is_err());
+        assert_eq!(corrupt_line("garden v1\nsize 0 4\n"), 2);
+        assert_eq!(corrupt_line(&format!("garden v1\nsize {} 2\n", u64::MAX)), 2);
+    }
+
+    #[test]
+    fn size_must_come_first_and_once() {
+        assert_eq!(corrupt_line("garden v1\ncell 0 0 50\nsize 2 2\n"), 2);
+        assert_eq!(corrupt_line("garden v1\nsize 2 2\ncell 0 0 50\nsize 2 2\n"), 4);
+        assert_eq!(corrupt_line("garden v1\nday 3\n"), 2);
+    }
+
+    #[test]
+    fn progress_must_fit_the_stage() {
+        // 生菜每阶段 4 天
+        assert!(Garden::from_text("garden v1\nsize 1 1\ncell 0 0 50 Lettuce Seed 3 100 3\n").is_ok());
+        assert_eq!(corrupt_line("garden v1\nsize 1 1\ncell 0 0 50 Lettuce Seed 4 100 3\n"), 3);
+        assert_eq!(corrupt_line("garden v1\nsize 1 1\ncell 0 0 50 Lettuce Seed 300 100 3\n"), 3);
+    }
+}
@@ -0,0 +1,312 @@
+// ---------------- 成绩等级与班级统计 ----------------
+/*
+    what: 把课程中 if score >= 90 … 80 … 60 的硬编码阶梯改为可配置的 GradingScale。
+    配置格式: 每行一个分数段，字段以空白分隔，# 开头为注释
+        下限 上限 字母 标签 pass|fail
+        90   100  A    优秀 pass
+    分数段为左闭右开 [下限, 上限)，最高段包含上限; 校验分数段之间无重叠、无空隙。
+    ClassReport: 从 "姓名,分数" 的 CSV 计算均值、中位数、标准差、各段人数和百分位排名。
+*/
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Band {
+    pub min: f64,
+    pub max: f64,
+    pub letter: String,
+    pub label: String,
+    pub pass: bool,
+}
+
+#[derive(Debug)]
+pub enum GradingError {
+    Io(io::Error),
+    Config { line: usize, reason: String },
+    Csv { line: usize, reason: String },
+    Empty,
+    InvalidBand { letter: String, min: f64, max: f64 },
+    Gap { below: String, above: String },
+    Overlap { below: String, above: String },
+}
+
+impl fmt::Display for GradingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GradingError::Io(e) => write!(f, "IO 错误: {e}"),
+            GradingError::Config { line, reason } => write!(f, "配置第 {line} 行错误: {reason}"),
+            GradingError::Csv { line, reason } => write!(f, "CSV 第 {line} 行错误: {reason}"),
+            GradingError::Empty => write!(f, "没有数据"),
+            GradingError::InvalidBand { letter, min, max } => {
+                write!(f, "分数段 {letter} 无效: 下限 {min} 必须小于上限 {max}，且都是有限数")
+            }
+            GradingError::Gap { below, above } => write!(f, "分数段 {below} 与 {above} 之间有空隙"),
+            GradingError::Overlap { below, above } => write!(f, "分数段 {below} 与 {above} 重叠"),
+        }
+    }
+}
+
+impl std::error::Error for GradingError {}
+
+impl From<io::Error> for GradingError {
+    fn from(err: io::Error) -> GradingError {
+        GradingError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradingScale {
+    // 按下限从低到高排列
+    bands: Vec<Band>,
+}
+
+impl Default for GradingScale {
+    // 课程中的阶梯: 90 优秀 / 80 良好 / 60 及格 / 不及格
+    fn default() -> Self {
+        let band = |min: f64, max: f64, letter: &str, label: &str, pass: bool| Band {
+            min,
+            max,
+            letter: letter.to_string(),
+            label: label.to_string(),
+            pass,
+        };
+        GradingScale {
+            bands: vec![
+                band(0.0, 60.0, "F", "不及格", false),
+                band(60.0, 80.0, "C", "及格", true),
+                band(80.0, 90.0, "B", "良好", true),
+                band(90.0, 100.0, "A", "优秀", true),
+            ],
+        }
+    }
+}
+
+impl GradingScale {
+    pub fn new(mut bands: Vec<Band>) -> Result<Self, GradingError> {
+        if bands.is_empty() {
+            return Err(GradingError::Empty);
+        }
+        if let Some(band) = bands.iter().find(|b| !b.min.is_finite() || !b.max.is_finite() || b.min >= b.max) {
+            return Err(GradingError::InvalidBand { letter: band.letter.clone(), min: band.min, max: band.max });
+        }
+        bands.sort_by(|a, b| a.min.total_cmp(&b.min));
+        for pair in bands.windows(2) {
+            let (below, above) = (&pair[0], &pair[1]);
+            let names = || (below.letter.clone(), above.letter.clone());
+            if below.max > above.min {
+                let (below, above) = names();
+                return Err(GradingError::Overlap { below, above });
+            }
+            if below.max < above.min {
+                let (below, above) = names();
+                return Err(GradingError::Gap { below, above });
+            }
+        }
+        Ok(GradingScale { bands })
+    }
+
+    pub fn from_config(text: &str) -> Result<Self, GradingError> {
+        let mut bands = Vec::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |reason: String| GradingError::Config { line: i + 1, reason };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [min, max, letter, label, pass] = fields[..] else {
+                return Err(err(format!("需要 5 个字段，实际 {}", fields.len())));
+            };
+            let min: f64 = min.parse().map_err(|_| err(format!("下限无效: {min}")))?;
+            let max: f64 = max.parse().map_err(|_| err(format!("上限无效: {max}")))?;
+            if !min.is_finite() || !max.is_finite() || min >= max {
+                return Err(err(format!("下限 {min} 必须小于上限 {max}")));
+            }
+            let pass = match pass {
+                "pass" | "及格" => true,
+                "fail" | "不及格" => false,
+                other => return Err(err(format!("应为 pass 或 fail: {other}"))),
+            };
+            bands.push(Band { min, max, letter: letter.to_string(), label: label.to_string(), pass });
+        }
+        Self::new(bands)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GradingError> {
+        Self::from_config(&fs::read_to_string(path)?)
+    }
+
+    pub fn bands(&self) -> &[Band] {
+        &self.bands
+    }
+
+    // 超出整体范围返回 None
+    pub fn grade(&self, score: f64) -> Option<&Band> {
+        let last = self.bands.last()?;
+        if score == last.max {
+            return Some(last);
+        }
+        self.bands.iter().find(|b| b.min <= score && score < b.max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudentResult {
+    pub name: String,
+    pub score: f64,
+    pub letter: String,
+    pub label: String,
+    pub pass: bool,
+    // 百分位排名: 低于该分数的比例 + 同分比例的一半
+    pub percentile_rank: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassReport {
+    pub students: Vec<StudentResult>,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    // 与 GradingScale::bands 顺序一致: (字母, 标签, 人数)
+    pub histogram: Vec<(String, String, usize)>,
+    pub pass_count: usize,
+}
+
+impl ClassReport {
+    // 出错时按第几条记录报告; 来自 CSV 时用 from_csv，报告的是文件行号
+    pub fn new(scores: &[(String, f64)], scale: &GradingScale) -> Result<Self, GradingError> {
+        let lines: Vec<usize> = (1..=scores.len()).collect();
+        Self::build(scores, &lines, scale)
+    }
+
+    // lines[i] 是 scores[i] 在来源中的行号
+    fn build(scores: &[(String, f64)], lines: &[usize], scale: &GradingScale) -> Result<Self, GradingError> {
+        if scores.is_empty() {
+            return Err(GradingError::Empty);
+        }
+        let n = scores.len() as f64;
+        let mut sorted: Vec<f64> = scores.iter().map(|(_, s)| *s).collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let mean = sorted.iter().sum::<f64>() / n;
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] };
+        // 总体标准差
+        let std_dev = (sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+        let mut histogram: Vec<(String, String, usize)> =
+            scale.bands().iter().map(|b| (b.letter.clone(), b.label.clone(), 0)).collect();
+        let mut students = Vec::with_capacity(scores.len());
+        for ((name, score), &line) in scores.iter().zip(lines) {
+            let band = scale.grade(*score).ok_or_else(|| GradingError::Csv {
+                line,
+                reason: format!("{name} 的分数 {score} 超出评分范围"),
+            })?;
+            if let Some(slot) = scale.bands().iter().position(|b| b == band) {
+                histogram[slot].2 += 1;
+            }
+            let below = sorted.partition_point(|s| s < score);
+            let equal = sorted[below..].iter().take_while(|s| *s == score).count();
+            students.push(StudentResult {
+                name: name.clone(),
+                score: *score,
+                letter: band.letter.clone(),
+                label: band.label.clone(),
+                pass: band.pass,
+                percentile_rank: (below as f64 + equal as f64 / 2.0) / n * 100.0,
+            });
+        }
+        let pass_count = students.iter().filter(|s| s.pass).count();
+        Ok(ClassReport { students, mean, median, std_dev, histogram, pass_count })
+    }
+
+    // CSV: 每行 "姓名,分数"，第一行分数无法解析时视为表头
+    pub fn from_csv(text: &str, scale: &GradingScale) -> Result<Self, GradingError> {
+        let mut scores = Vec::new();
+        let mut lines = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let err = |reason: String| GradingError::Csv { line: i + 1, reason };
+            let Some((name, score)) = line.rsplit_once(',') else {
+                return Err(err(format!("缺少逗号: {line}")));
+            };
+            match score.trim().parse::<f64>() {
+                Ok(score) if score.is_finite() => {
+                    scores.push((name.trim().to_string(), score));
+                    lines.push(i + 1);
+                }
+                _ if i == 0 => continue,
+                _ => return Err(err(format!("分数无效: {}", score.trim()))),
+            }
+        }
+        Self::build(&scores, &lines, scale)
+    }
+
+    pub fn load(path: impl AsRef<Path>, scale: &GradingScale) -> Result<Self, GradingError> {
+        Self::from_csv(&fs::read_to_string(path)?, scale)
+    }
+}
+
+impl fmt::Display for ClassReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.students.len();
+        writeln!(f, "人数: {total}，及格: {}（{:.1}%）", self.pass_count, self.pass_count as f64 / total as f64 * 100.0)?;
+        writeln!(f, "平均分: {:.2}，中位数: {:.2}，标准差: {:.2}", self.mean, self.median, self.std_dev)?;
+        writeln!(f, "分布:")?;
+        for (letter, label, count) in self.histogram.iter().rev() {
+            writeln!(f, "  {letter} {label}\t{count:>3} {}", "#".repeat(*count))?;
+        }
+        writeln!(f, "明细:")?;
+        for s in &self.students {
+            writeln!(f, "  {}\t{:>6.1}\t{} {}\t百分位 {:.1}", s.name, s.score, s.letter, s.label, s.percentile_rank)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn band(min: f64, max: f64, letter: &str) -> Band {
+        Band { min, max, letter: letter.to_string(), label: String::new(), pass: true }
+    }
+
+    #[test]
+    fn new_checks_each_band() {
+        for (min, max) in [(60.0, 60.0), (80.0, 60.0), (f64::NAN, 60.0), (0.0, f64::INFINITY)] {
+            let err = GradingScale::new(vec![band(min, max, "X")]).unwrap_err();
+            assert!(matches!(err, GradingError::InvalidBand { .. }), "{min} {max}: {err}");
+        }
+        assert!(GradingScale::new(vec![band(0.0, 50.0, "F"), band(50.0, 100.0, "P")]).is_ok());
+    }
+
+    #[test]
+    fn out_of_range_score_reports_csv_line() {
+        let scale = GradingScale::default();
+        let csv = "姓名,分数\n\nsui,88\n\nsonic,120\n";
+        match ClassReport::from_csv(csv, &scale).unwrap_err() {
+            GradingError::Csv { line, reason } => {
+                assert_eq!(line, 5);
+                assert!(reason.contains("sonic"));
+            }
+            other => panic!("应为 CSV 错误: {other}"),
+        }
+    }
+
+    #[test]
+    fn report_statistics() {
+        let scale = GradingScale::default();
+        let report = ClassReport::from_csv("name,score\na,90\nb,70\nc,50\nd,100\n", &scale).unwrap();
+        assert_eq!(report.mean, 77.5);
+        assert_eq!(report.median, 80.0);
+        assert_eq!(report.pass_count, 3);
+        assert_eq!(report.histogram.iter().map(|h| h.2).collect::<Vec<_>>(), [1, 1, 0, 2]);
+        assert_eq!(report.students[3].letter, "A");
+    }
+}
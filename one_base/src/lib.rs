@@ -0,0 +1,20 @@
+// ---------------- one_base 库 ----------------
+// 把基础课程中的类型整理为可复用模块，main.rs 继续保留课程示例
+
+pub mod auth;
+pub mod chinese_numeral;
+pub mod coin;
+pub mod color;
+pub mod duration;
+pub mod garden;
+pub mod grading;
+pub mod log;
+pub mod message;
+pub mod money;
+pub mod radix;
+pub mod registry;
+pub mod style;
+pub mod table;
+pub mod user;
+pub mod vending;
+pub mod width;
@@ -0,0 +1,587 @@
+// ---------------- 日志 ----------------
+/*
+    what: 替代到处散落的 println! / dbg!，诊断信息带级别、来源模块和键值字段。
+    how:
+        - 级别: Error > Warn > Info > Debug > Trace;
+        - 目标: 默认是调用处的 module_path!()，如 one_base::vending; 也可以用 target: 指定;
+          其他 crate 依赖 one_base 后用 one_base::info!(...) 记录，目标就是那个 crate 的模块路径，
+          这时 RUST_BASE_LOG=three_impl=debug 才会匹配; 只有 one_base 自己记录时，过滤规则要写 one_base::…;
+        - 宏: info!("已保存 {} 个用户", n) 或 info!(path = p.display(), count = n; "已保存")
+              或 info!(target: "three_impl", "…");
+        - 过滤: RUST_BASE_LOG=warn,one_base::garden=debug,one_base::vending=off
+                 按最长的模块路径前缀匹配，没有匹配时使用默认级别（未设置时为 info）;
+        - 输出: StderrSink（人读，终端中按级别着色）、JsonSink（每行一个 JSON）、
+                MemorySink（记录下来供检查）;
+        - 全局 Logger 只能 init 一次; 测试用 log::scoped(logger) 为当前线程临时替换，
+          守卫释放时恢复，每个测试各用一个 MemorySink，并行运行也互不干扰;
+    why: 宏先比较一个原子变量中的最高级别，关闭的级别不会格式化参数，也不会求值字段。
+*/
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::fmt;
+use std::marker::PhantomData;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::color::Color;
+use crate::style::{self, ColorMode, Style};
+
+pub const ENV_VAR: &str = "RUST_BASE_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+
+    fn style(self) -> Style {
+        match self {
+            Level::Error => style::ERROR,
+            Level::Warn => style::WARNING,
+            Level::Info => style::SUCCESS,
+            Level::Debug => Style::new().fg(Color::rgb(0, 135, 215)),
+            Level::Trace => style::DIM,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "未知的日志级别: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(ParseLevelError(s.to_string())),
+        }
+    }
+}
+
+// ---------- 过滤 ----------
+
+// None 表示 off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    directives: Vec<(String, Option<Level>)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter { default: Some(Level::Info), directives: Vec::new() }
+    }
+}
+
+fn parse_level_or_off(s: &str) -> Result<Option<Level>, ParseLevelError> {
+    if s.trim().eq_ignore_ascii_case("off") { Ok(None) } else { s.parse().map(Some) }
+}
+
+impl FromStr for Filter {
+    type Err = ParseLevelError;
+
+    // "warn,one_base::garden=debug"，单独的级别设置默认值
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    filter.directives.push((target.trim().to_string(), parse_level_or_off(level)?));
+                }
+                None => filter.default = parse_level_or_off(directive)?,
+            }
+        }
+        // 长前缀优先
+        filter.directives.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    pub fn from_env() -> Result<Filter, ParseLevelError> {
+        env::var(ENV_VAR).map_or(Ok(Filter::default()), |v| v.parse())
+    }
+
+    fn level_for(&self, target: &str) -> Option<Level> {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| {
+                target == prefix || target.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        self.level_for(target).is_some_and(|max| level <= max)
+    }
+
+    // 所有规则中最宽松的级别，用于宏里的快速判断
+    fn max_level(&self) -> Option<Level> {
+        self.directives.iter().map(|(_, l)| *l).chain([self.default]).max().flatten()
+    }
+}
+
+// ---------- 记录与输出 ----------
+
+pub struct Record<'a> {
+    pub level: Level,
+    pub target: &'a str,
+    pub message: fmt::Arguments<'a>,
+    pub fields: &'a [(&'a str, &'a dyn fmt::Display)],
+}
+
+pub trait Sink: Send + Sync {
+    fn write(&self, record: &Record);
+}
+
+// 人读格式: INFO  one_base::vending: 出货 slot=A1
+pub struct StderrSink {
+    mode: ColorMode,
+}
+
+impl StderrSink {
+    pub fn new() -> Self {
+        StderrSink { mode: ColorMode::stderr() }
+    }
+}
+
+impl Default for StderrSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn format_human(record: &Record, mode: ColorMode) -> String {
+    let level = record.level.style().paint(format!("{:<5}", record.level)).render(mode);
+    let mut line = format!("{level} {}: {}", record.target, record.message);
+    for (key, value) in record.fields {
+        line.push_str(&format!(" {key}={value}"));
+    }
+    line
+}
+
+impl Sink for StderrSink {
+    fn write(&self, record: &Record) {
+        let line = format_human(record, self.mode);
+        // 日志写失败时没有更好的去处，忽略
+        let _ = writeln!(io::stderr().lock(), "{line}");
+    }
+}
+
+// 每行一个 JSON 对象: {"ts":1760000000000,"level":"INFO","target":"...","msg":"...","fields":{...}}
+pub struct JsonSink<W: Write + Send> {
+    out: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonSink<W> {
+    pub fn new(out: W) -> Self {
+        JsonSink { out: Mutex::new(out) }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn format_json(record: &Record) -> String {
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+    let fields: Vec<String> = record
+        .fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(&value.to_string())))
+        .collect();
+    format!(
+        "{{\"ts\":{ts},\"level\":\"{}\",\"target\":{},\"msg\":{},\"fields\":{{{}}}}}",
+        record.level,
+        json_string(record.target),
+        json_string(&record.message.to_string()),
+        fields.join(",")
+    )
+}
+
+impl<W: Write + Send> Sink for JsonSink<W> {
+    fn write(&self, record: &Record) {
+        let line = format_json(record);
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(out, "{line}");
+    }
+}
+
+// 保存下来的一条日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedRecord {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl OwnedRecord {
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+// 克隆后共享同一份记录，一份交给 Logger，一份留着检查
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    records: Arc<Mutex<Vec<OwnedRecord>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<OwnedRecord> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl Sink for MemorySink {
+    fn write(&self, record: &Record) {
+        let owned = OwnedRecord {
+            level: record.level,
+            target: record.target.to_string(),
+            message: record.message.to_string(),
+            fields: record.fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        };
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).push(owned);
+    }
+}
+
+// ---------- Logger ----------
+
+pub struct Logger {
+    filter: Filter,
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Logger {
+    pub fn new(filter: Filter) -> Self {
+        Logger { filter, sinks: Vec::new() }
+    }
+
+    pub fn sink(mut self, sink: impl Sink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        self.filter.enabled(target, level)
+    }
+
+    pub fn log(&self, record: &Record) {
+        if self.enabled(record.target, record.level) {
+            self.sinks.iter().for_each(|sink| sink.write(record));
+        }
+    }
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+// 0 表示全部关闭，未初始化时也是 0
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlreadyInitialized;
+
+impl fmt::Display for AlreadyInitialized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "日志已经初始化")
+    }
+}
+
+impl std::error::Error for AlreadyInitialized {}
+
+// 全局只能初始化一次
+pub fn init(logger: Logger) -> Result<(), AlreadyInitialized> {
+    let max = logger.filter.max_level().map_or(0, |l| l as u8);
+    LOGGER.set(logger).map_err(|_| AlreadyInitialized)?;
+    MAX_LEVEL.store(max, Ordering::Relaxed);
+    Ok(())
+}
+
+// 命令行工具的默认配置: 过滤规则取自 RUST_BASE_LOG，输出到 stderr
+pub fn init_from_env() -> Result<(), Box<dyn std::error::Error>> {
+    let filter = Filter::from_env()?;
+    init(Logger::new(filter).sink(StderrSink::new()))?;
+    Ok(())
+}
+
+// ---------- 线程内临时替换 ----------
+
+thread_local! {
+    // 栈顶的 Logger 优先于全局 Logger，允许嵌套
+    static SCOPED: RefCell<Vec<Logger>> = const { RefCell::new(Vec::new()) };
+    // 栈顶 Logger 的最高级别，0 表示没有
+    static SCOPED_MAX: Cell<u8> = const { Cell::new(0) };
+}
+
+// 守卫不能跨线程移动: 它恢复的是创建它的线程的状态
+pub struct ScopedGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+// 当前线程在守卫存活期间改用 logger，不影响其他线程和全局 Logger
+pub fn scoped(logger: Logger) -> ScopedGuard {
+    SCOPED.with_borrow_mut(|stack| {
+        stack.push(logger);
+        refresh_scoped_max(stack);
+    });
+    ScopedGuard { _not_send: PhantomData }
+}
+
+impl Drop for ScopedGuard {
+    fn drop(&mut self) {
+        SCOPED.with_borrow_mut(|stack| {
+            stack.pop();
+            refresh_scoped_max(stack);
+        });
+    }
+}
+
+fn refresh_scoped_max(stack: &[Logger]) {
+    let max = stack.last().and_then(|l| l.filter.max_level()).map_or(0, |l| l as u8);
+    SCOPED_MAX.set(max);
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn __enabled(level: Level) -> bool {
+    let level = level as u8;
+    level <= MAX_LEVEL.load(Ordering::Relaxed) || level <= SCOPED_MAX.get()
+}
+
+#[doc(hidden)]
+pub fn __log(level: Level, target: &str, message: fmt::Arguments, fields: &[(&str, &dyn fmt::Display)]) {
+    let record = Record { level, target, message, fields };
+    let handled = SCOPED.with_borrow(|stack| match stack.last() {
+        Some(logger) => {
+            logger.log(&record);
+            true
+        }
+        None => false,
+    });
+    if !handled && let Some(logger) = LOGGER.get() {
+        logger.log(&record);
+    }
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, target: $target:expr, $($key:ident = $value:expr),+ ; $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::log::__enabled(level) {
+            $crate::log::__log(
+                level,
+                $target,
+                format_args!($($arg)+),
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),+],
+            );
+        }
+    }};
+    ($level:expr, target: $target:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::log::__enabled(level) {
+            $crate::log::__log(level, $target, format_args!($($arg)+), &[]);
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!($level, target: module_path!(), $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_logger(filter: &str) -> (Logger, MemorySink) {
+        let sink = MemorySink::new();
+        (Logger::new(filter.parse().unwrap()).sink(sink.clone()), sink)
+    }
+
+    #[test]
+    fn filter_uses_longest_prefix() {
+        let filter: Filter = "warn,one_base::garden=debug,one_base::garden::plot=off,three_impl=debug".parse().unwrap();
+        assert!(filter.enabled("one_base::garden::storage", Level::Debug));
+        assert!(!filter.enabled("one_base::garden::plot", Level::Error));
+        assert!(!filter.enabled("one_base::gardener", Level::Info));
+        assert!(filter.enabled("one_base::vending", Level::Warn));
+        assert!(filter.enabled("three_impl", Level::Debug));
+        assert!(filter.enabled("three_impl::shape", Level::Debug));
+        assert!("verbose".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn scoped_logger_captures_macros() {
+        let (logger, sink) = memory_logger("debug");
+        let _guard = scoped(logger);
+        crate::info!(slot = "A1", price = 65; "出货 {}", "可乐");
+        crate::trace!("被过滤");
+        let records = sink.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, Level::Info);
+        assert_eq!(records[0].target, "one_base::log::tests");
+        assert_eq!(records[0].message, "出货 可乐");
+        assert_eq!(records[0].field("price"), Some("65"));
+    }
+
+    #[test]
+    fn each_test_gets_its_own_sink() {
+        // 和上一个测试并行运行时也只看到自己的记录
+        let (logger, sink) = memory_logger("info");
+        {
+            let _guard = scoped(logger);
+            crate::warn!("只属于这个测试");
+        }
+        crate::warn!("守卫释放之后");
+        assert_eq!(sink.records().len(), 1);
+
+        let handle = std::thread::spawn(|| {
+            let (logger, sink) = memory_logger("info");
+            let _guard = scoped(logger);
+            crate::info!("另一个线程");
+            sink.records().len()
+        });
+        assert_eq!(handle.join().unwrap(), 1);
+        assert_eq!(sink.records().len(), 1);
+    }
+
+    #[test]
+    fn nested_scopes_restore_outer() {
+        let (outer, outer_sink) = memory_logger("info");
+        let (inner, inner_sink) = memory_logger("info");
+        let _outer = scoped(outer);
+        {
+            let _inner = scoped(inner);
+            crate::info!("内层");
+        }
+        crate::info!("外层");
+        assert_eq!(inner_sink.records()[0].message, "内层");
+        assert_eq!(outer_sink.records().len(), 1);
+        assert_eq!(outer_sink.records()[0].message, "外层");
+    }
+
+    #[test]
+    fn explicit_target_matches_other_crate_filter() {
+        let (logger, sink) = memory_logger("warn,three_impl=debug");
+        let _guard = scoped(logger);
+        crate::debug!(target: "three_impl", "面积 {}", 50);
+        crate::debug!(target: "three_impl", width = 5; "缩放");
+        crate::debug!("one_base 的 debug 被过滤");
+        let targets: Vec<String> = sink.records().into_iter().map(|r| r.target).collect();
+        assert_eq!(targets, ["three_impl", "three_impl"]);
+    }
+
+    #[test]
+    fn disabled_levels_skip_field_evaluation() {
+        struct Loud<'a>(&'a Cell<u32>);
+        impl fmt::Display for Loud<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+                write!(f, "loud")
+            }
+        }
+        let calls = Cell::new(0);
+        let (logger, _sink) = memory_logger("error");
+        let _guard = scoped(logger);
+        crate::debug!(value = Loud(&calls); "不会格式化");
+        assert_eq!(calls.get(), 0);
+        crate::error!(value = Loud(&calls); "会格式化");
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn json_lines_escape_text() {
+        let fields: [(&str, &dyn fmt::Display); 1] = [("path", &"a\"b")];
+        let record = Record { level: Level::Warn, target: "t", message: format_args!("行1\n行2"), fields: &fields };
+        let line = format_json(&record);
+        assert!(line.contains(r#""level":"WARN""#));
+        assert!(line.contains(r#""msg":"行1\n行2""#));
+        assert!(line.contains(r#""fields":{"path":"a\"b"}"#));
+    }
+}
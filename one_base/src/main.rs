@@ -1,5 +1,7 @@
 // ---------------- 1、基础语法 ----------------  
 // const THREE_HOURS_IN_SECONDS: u32 = 60 * 60 * 3;
+// 也可以用 const fn 写出单位，避免手算:
+// const THREE_HOURS_IN_SECONDS: u64 = one_base::duration::hours(3).as_secs();
 
 // fn main() {
     
@@ -92,7 +94,8 @@
 //         println!("不及格！")
 //     }
 
-//     // build_user("jamie@.com", "Jamie");
+//     // build_user 参数是 String，字面量 &str 需要转换
+//     // build_user(String::from("jamie@example.com"), String::from("Jamie"));
 
 // }
 
@@ -368,13 +371,28 @@
 // ---------------- 模块 ----------------
 
 // 模块定义
-mod garden {
-    // 由于模块中的函数默认是私有的 private，所以需要使用pub让外部调用
-    pub fn plant(){
-        println!("种植蔬菜!");
-    }
-}
+// mod garden {
+//     // 由于模块中的函数默认是私有的 private，所以需要使用pub让外部调用
+//     pub fn plant(){
+//         println!("种植蔬菜!");
+//     }
+// }
+
+// 模块拆分到文件: garden 已移到库中的 src/garden.rs 与 src/garden/ 目录，
+// 通过 crate 名 one_base 访问库里的模块
+use one_base::garden;
 
 fn main(){
-    garden::plant(); // 在同一文件中访问模块中的函数
+    garden::plant(); // 访问库中模块的函数
+
+    // 模拟: 相同的种子得到相同的结果
+    let mut g = garden::Garden::new(4, 2, 9527).unwrap();
+    g.sow(0, 0, garden::Species::Lettuce).unwrap();
+    g.sow(1, 0, garden::Species::Carrot).unwrap();
+    for _ in 0..30 {
+        g.tend();
+        g.tick();
+    }
+    print!("第 {} 天（{}）:\n{}", g.day(), g.season(), g.plot().render());
+    println!("收获: {:?}", g.harvest_all());
 }
\ No newline at end of file
@@ -0,0 +1,23 @@
+// ---------------- 枚举 Message ----------------
+// 来自 enum 课程，ChangeColor 改为携带 Color，不再是三个 i32
+
+use crate::color::Color;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(Color),
+}
+
+impl Message {
+    pub fn call(&self) {
+        match self {
+            Message::Quit => println!("退出"),
+            Message::Move { x, y } => println!("移动到 ({x}, {y})"),
+            Message::Write(text) => println!("写入: {text}"),
+            Message::ChangeColor(color) => println!("颜色改为 {color}"),
+        }
+    }
+}
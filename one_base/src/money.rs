@@ -0,0 +1,383 @@
+// ---------------- 金额 Money ----------------
+/*
+    what: 定点金额类型，以最小单位（分）存储为 i64，并用泛型参数标记币种。
+    why: value_in_cents 返回 u8，最多 255 分，无法表示合计金额;
+    how:
+        - Money<Usd> 与 Money<Cny> 是不同类型，混用在编译期报错;
+        - 运算只提供 checked_*，溢出返回 MoneyError::Overflow;
+        - allocate/split 按比例分配，余数逐分补给前几份，总额不丢一分;
+        - 解析与格式化: "$1.25"、"¥9,527.00"，币种符号不符返回 CurrencyMismatch;
+          小数点两侧都必须有数字，"1." 和 ".5" 都不接受;
+*/
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+// 币种标记 trait: 每个币种是一个零大小类型
+pub trait Currency {
+    const CODE: &'static str;
+    const SYMBOL: &'static str;
+    const MINOR_DIGITS: u32;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Usd;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cny;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Eur;
+
+impl Currency for Usd {
+    const CODE: &'static str = "USD";
+    const SYMBOL: &'static str = "$";
+    const MINOR_DIGITS: u32 = 2;
+}
+
+impl Currency for Cny {
+    const CODE: &'static str = "CNY";
+    const SYMBOL: &'static str = "¥";
+    const MINOR_DIGITS: u32 = 2;
+}
+
+impl Currency for Eur {
+    const CODE: &'static str = "EUR";
+    const SYMBOL: &'static str = "€";
+    const MINOR_DIGITS: u32 = 2;
+}
+
+// 解析时用来识别"其他币种"的符号表
+const KNOWN: [(&str, &str); 3] = [(Usd::SYMBOL, Usd::CODE), (Cny::SYMBOL, Cny::CODE), (Eur::SYMBOL, Eur::CODE)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    Overflow,
+    CurrencyMismatch { expected: &'static str, found: String },
+    InvalidRatios,
+    Parse(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoneyError::Overflow => write!(f, "金额溢出"),
+            MoneyError::CurrencyMismatch { expected, found } => {
+                write!(f, "币种不符: 期望 {expected}，实际 {found}")
+            }
+            MoneyError::InvalidRatios => write!(f, "分配比例无效"),
+            MoneyError::Parse(s) => write!(f, "无法解析金额: {s}"),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+pub struct Money<C: Currency> {
+    minor: i64,
+    currency: PhantomData<C>,
+}
+
+// 手写 trait 实现: derive 会要求 C 本身实现这些 trait
+impl<C: Currency> Clone for Money<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Currency> Copy for Money<C> {}
+
+impl<C: Currency> PartialEq for Money<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.minor == other.minor
+    }
+}
+
+impl<C: Currency> Eq for Money<C> {}
+
+impl<C: Currency> PartialOrd for Money<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Currency> Ord for Money<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.minor.cmp(&other.minor)
+    }
+}
+
+impl<C: Currency> Hash for Money<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.minor.hash(state);
+    }
+}
+
+impl<C: Currency> Default for Money<C> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<C: Currency> fmt::Debug for Money<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Money({} {})", C::CODE, self.minor)
+    }
+}
+
+impl<C: Currency> Money<C> {
+    pub const ZERO: Self = Money { minor: 0, currency: PhantomData };
+
+    // 最小单位，如美分、人民币分
+    pub const fn from_minor(minor: i64) -> Self {
+        Money { minor, currency: PhantomData }
+    }
+
+    pub fn from_major(major: i64) -> Result<Self, MoneyError> {
+        major
+            .checked_mul(Self::scale())
+            .map(Self::from_minor)
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub const fn minor(self) -> i64 {
+        self.minor
+    }
+
+    pub fn code(self) -> &'static str {
+        C::CODE
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, MoneyError> {
+        self.minor.checked_add(other.minor).map(Self::from_minor).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, MoneyError> {
+        self.minor.checked_sub(other.minor).map(Self::from_minor).ok_or(MoneyError::Overflow)
+    }
+
+    // 单价 × 数量
+    pub fn checked_mul(self, quantity: i64) -> Result<Self, MoneyError> {
+        self.minor.checked_mul(quantity).map(Self::from_minor).ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sum<I: IntoIterator<Item = Self>>(iter: I) -> Result<Self, MoneyError> {
+        iter.into_iter().try_fold(Self::ZERO, Self::checked_add)
+    }
+
+    // 按比例分配，例如 [1, 1, 1] 把 $1.00 分为 0.34 / 0.33 / 0.33
+    // 除不尽的零头从前往后分给比例非 0 的份额，比例为 0 的份额始终是 0
+    pub fn allocate(self, ratios: &[u32]) -> Result<Vec<Self>, MoneyError> {
+        let total: u64 = ratios.iter().map(|&r| r as u64).sum();
+        if total == 0 {
+            return Err(MoneyError::InvalidRatios);
+        }
+        let mut shares: Vec<i64> = ratios
+            .iter()
+            .map(|&r| (self.minor as i128 * r as i128 / total as i128) as i64)
+            .collect();
+        let mut remainder = self.minor - shares.iter().sum::<i64>();
+        let step = remainder.signum();
+        // 每个非 0 份额的舍入误差不到 1 分，零头一定少于非 0 份额的个数
+        for (share, _) in shares.iter_mut().zip(ratios).filter(|(_, r)| **r > 0) {
+            if remainder == 0 {
+                break;
+            }
+            *share += step;
+            remainder -= step;
+        }
+        Ok(shares.into_iter().map(Self::from_minor).collect())
+    }
+
+    // 平均分成 n 份
+    pub fn split(self, n: usize) -> Result<Vec<Self>, MoneyError> {
+        self.allocate(&vec![1; n])
+    }
+
+    fn scale() -> i64 {
+        10i64.pow(C::MINOR_DIGITS)
+    }
+}
+
+// 千分位分组: 9527 -> "9,527"
+fn group_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+impl<C: Currency> fmt::Display for Money<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let abs = self.minor.unsigned_abs();
+        let scale = Self::scale() as u64;
+        let sign = if self.minor < 0 { "-" } else { "" };
+        write!(f, "{sign}{}{}", C::SYMBOL, group_thousands(abs / scale))?;
+        if C::MINOR_DIGITS > 0 {
+            write!(f, ".{:0width$}", abs % scale, width = C::MINOR_DIGITS as usize)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Currency> FromStr for Money<C> {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_err = || MoneyError::Parse(s.to_string());
+        let text = s.trim();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, text),
+        };
+
+        // 币种: 符号或代码前缀，识别到其他币种时报 CurrencyMismatch
+        let text = if let Some(rest) = text.strip_prefix(C::SYMBOL).or_else(|| text.strip_prefix(C::CODE)) {
+            rest.trim_start()
+        } else if let Some((_, code)) = KNOWN
+            .iter()
+            .find(|(symbol, code)| text.starts_with(symbol) || text.starts_with(code))
+        {
+            return Err(MoneyError::CurrencyMismatch { expected: C::CODE, found: code.to_string() });
+        } else {
+            text
+        };
+
+        let (int_part, frac_part) = match text.split_once('.') {
+            Some((_, "")) => return Err(parse_err()),
+            Some((i, f)) => (i, f),
+            None => (text, ""),
+        };
+        if int_part.is_empty() || frac_part.len() > C::MINOR_DIGITS as usize {
+            return Err(parse_err());
+        }
+        // 千分位: 第一组 1~3 位，其余每组 3 位
+        let groups: Vec<&str> = int_part.split(',').collect();
+        let grouping_ok = groups.len() == 1
+            || (!groups[0].is_empty() && groups[0].len() <= 3 && groups[1..].iter().all(|g| g.len() == 3));
+        let all_digits = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+        if !grouping_ok || !groups.iter().all(|g| all_digits(g)) || !all_digits(frac_part) {
+            return Err(parse_err());
+        }
+
+        let major: i64 = groups.concat().parse().map_err(|_| MoneyError::Overflow)?;
+        let frac: i64 = if frac_part.is_empty() {
+            0
+        } else {
+            frac_part.parse::<i64>().map_err(|_| parse_err())? * 10i64.pow(C::MINOR_DIGITS - frac_part.len() as u32)
+        };
+        let minor = major
+            .checked_mul(Self::scale())
+            .and_then(|m| m.checked_add(frac))
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Self::from_minor(if negative { -minor } else { minor }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minors(shares: &[Money<Usd>]) -> Vec<i64> {
+        shares.iter().map(|m| m.minor).collect()
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let usd: Money<Usd> = "$1.25".parse().unwrap();
+        assert_eq!(usd, Money::from_minor(125));
+        assert_eq!(usd.to_string(), "$1.25");
+        let cny: Money<Cny> = "¥9,527.00".parse().unwrap();
+        assert_eq!(cny, Money::from_minor(952_700));
+        assert_eq!(cny.to_string(), "¥9,527.00");
+        for minor in [0, 1, -1, 5, -125, 100_000_000, i64::MAX, i64::MIN + 1] {
+            let money = Money::<Eur>::from_minor(minor);
+            assert_eq!(money.to_string().parse::<Money<Eur>>(), Ok(money), "{money}");
+        }
+    }
+
+    #[test]
+    fn parse_accepts_variants() {
+        let parse = |s: &str| s.parse::<Money<Usd>>().map(|m| m.minor);
+        assert_eq!(parse("-$1.25"), Ok(-125));
+        assert_eq!(parse("- $1.25"), Ok(-125));
+        assert_eq!(parse("USD 3"), Ok(300));
+        assert_eq!(parse("1234.5"), Ok(123_450));
+        assert_eq!(parse("  $1,234,567.89 "), Ok(123_456_789));
+        assert_eq!(Money::<Usd>::from_minor(-123_456_789).to_string(), "-$1,234,567.89");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_amounts() {
+        for bad in ["", "$", "1,23", ",123", "1,2345", "12,34,567", "1.234", "$1.2.3", "1.", ".5", "1e3", "$ -1", "abc"] {
+            assert_eq!(bad.parse::<Money<Usd>>(), Err(MoneyError::Parse(bad.to_string())), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn parse_reports_currency_mismatch() {
+        assert_eq!(
+            "€5".parse::<Money<Usd>>(),
+            Err(MoneyError::CurrencyMismatch { expected: "USD", found: "EUR".to_string() })
+        );
+        assert_eq!(
+            "CNY 5".parse::<Money<Usd>>(),
+            Err(MoneyError::CurrencyMismatch { expected: "USD", found: "CNY".to_string() })
+        );
+    }
+
+    #[test]
+    fn parse_reports_overflow() {
+        assert_eq!("$92,233,720,368,547,758.07".parse::<Money<Usd>>().map(|m| m.minor), Ok(i64::MAX));
+        assert_eq!("$92,233,720,368,547,758.08".parse::<Money<Usd>>(), Err(MoneyError::Overflow));
+        assert_eq!("99999999999999999999".parse::<Money<Usd>>(), Err(MoneyError::Overflow));
+        let max = Money::<Usd>::from_minor(i64::MAX);
+        assert_eq!(max.checked_add(Money::from_minor(1)), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn allocate_spreads_leftover_cents() {
+        let shares = Money::<Usd>::from_minor(100).allocate(&[1, 1, 1]).unwrap();
+        assert_eq!(minors(&shares), [34, 33, 33]);
+        let shares = Money::<Usd>::from_minor(-100).allocate(&[1, 1, 1]).unwrap();
+        assert_eq!(minors(&shares), [-34, -33, -33]);
+    }
+
+    #[test]
+    fn zero_ratios_get_nothing() {
+        let shares = Money::<Usd>::from_minor(100).allocate(&[0, 1, 1, 1]).unwrap();
+        assert_eq!(minors(&shares), [0, 34, 33, 33]);
+        let shares = Money::<Usd>::from_minor(-5).allocate(&[1, 0, 1, 0, 1]).unwrap();
+        assert_eq!(minors(&shares), [-2, 0, -2, 0, -1]);
+        let shares = Money::<Usd>::from_minor(7).allocate(&[0, 0, 3]).unwrap();
+        assert_eq!(minors(&shares), [0, 0, 7]);
+    }
+
+    #[test]
+    fn allocation_preserves_total() {
+        for amount in [0, 1, 99, 100, 101, 12_345, -777] {
+            let shares = Money::<Usd>::from_minor(amount).allocate(&[0, 3, 0, 5, 7]).unwrap();
+            assert_eq!(shares.iter().map(|m| m.minor).sum::<i64>(), amount);
+            assert_eq!(shares[0].minor, 0);
+            assert_eq!(shares[2].minor, 0);
+        }
+    }
+
+    #[test]
+    fn allocate_rejects_all_zero_ratios() {
+        assert!(matches!(Money::<Usd>::from_minor(100).allocate(&[0, 0]), Err(MoneyError::InvalidRatios)));
+        assert!(matches!(Money::<Usd>::from_minor(100).split(0), Err(MoneyError::InvalidRatios)));
+    }
+}
@@ -0,0 +1,505 @@
+// ---------------- 任意进制 ----------------
+/*
+    what: 在 {:b} / {:x} / {:o} 之外，支持 2~36 任意进制的格式化与解析。
+    how:
+        - Radix::new(进制) 之后链式设置分组、大小写、前缀、补零;
+        - 分组: group(4, '_') 得到 1_0000_0000，解析时自动忽略分隔符;
+        - 负数: 默认输出 -前缀; twos_complement(值, 位宽) 给出补码视图;
+        - 大整数: 内部统一转换为 BigInt（u32 分段存储），i128/u128 之外的宽度同样可用;
+*/
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadixError {
+    InvalidRadix(u32),
+    Empty,
+    InvalidDigit { ch: char, radix: u32 },
+    OutOfRange { bits: u32 },
+}
+
+impl fmt::Display for RadixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RadixError::InvalidRadix(r) => write!(f, "进制必须在 2~36 之间: {r}"),
+            RadixError::Empty => write!(f, "没有数字"),
+            RadixError::InvalidDigit { ch, radix } => write!(f, "{radix} 进制中无效的数字: {ch:?}"),
+            RadixError::OutOfRange { bits } => write!(f, "数值超出 {bits} 位的表示范围"),
+        }
+    }
+}
+
+impl std::error::Error for RadixError {}
+
+// ---------- 大整数 ----------
+
+// 无符号大整数: 小端序 u32 分段，最高段非零（零为空 Vec）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // 2 的 n 次方
+    pub fn pow2(n: u32) -> Self {
+        let mut limbs = vec![0u32; n as usize / 32 + 1];
+        limbs[n as usize / 32] = 1 << (n % 32);
+        BigUint { limbs }
+    }
+
+    // 有效位数，0 的位数为 0
+    pub fn bits(&self) -> u32 {
+        match self.limbs.last() {
+            Some(top) => (self.limbs.len() as u32 - 1) * 32 + (32 - top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    // 大端字节序（如网络协议中的字段）
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let mut limbs: Vec<u32> = bytes
+            .rchunks(4)
+            .map(|chunk| chunk.iter().fold(0u32, |acc, &b| (acc << 8) | b as u32))
+            .collect();
+        trim(&mut limbs);
+        BigUint { limbs }
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.limbs.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+        let leading = bytes.iter().take_while(|&&b| b == 0).count();
+        bytes.drain(..leading);
+        bytes
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0u128, |acc, &l| (acc << 32) | l as u128))
+    }
+
+    // self = self * mul + add
+    fn mul_add_small(&mut self, mul: u32, add: u32) {
+        let mut carry = add as u64;
+        for limb in self.limbs.iter_mut() {
+            let v = *limb as u64 * mul as u64 + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    // self = self / div，返回余数
+    fn div_rem_small(&mut self, div: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let v = (rem << 32) | *limb as u64;
+            *limb = (v / div as u64) as u32;
+            rem = v % div as u64;
+        }
+        trim(&mut self.limbs);
+        rem as u32
+    }
+
+    // self - other，要求 self >= other
+    fn sub(&self, other: &BigUint) -> BigUint {
+        let mut limbs = self.limbs.clone();
+        let mut borrow = 0i64;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let v = *limb as i64 - other.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = (v < 0) as i64;
+            *limb = (v + (borrow << 32)) as u32;
+        }
+        trim(&mut limbs);
+        BigUint { limbs }
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(v: u128) -> Self {
+        let mut limbs: Vec<u32> = (0..4).map(|i| (v >> (32 * i)) as u32).collect();
+        trim(&mut limbs);
+        BigUint { limbs }
+    }
+}
+
+// 有符号大整数: 符号 + 绝对值，零总是非负
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn new(negative: bool, magnitude: BigUint) -> Self {
+        BigInt { negative: negative && !magnitude.is_zero(), magnitude }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let m = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(m)
+        } else {
+            i128::try_from(m).ok()
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative { None } else { self.magnitude.to_u128() }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::new(false, magnitude)
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(v: $t) -> Self {
+                BigInt::from(BigUint::from(v as u128))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(v: $t) -> Self {
+                BigInt::new(v < 0, BigUint::from((v as i128).unsigned_abs()))
+            }
+        }
+    )*};
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "", &digits(self, 10, false))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &digits(&self.magnitude, 10, false))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = RadixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Radix::DECIMAL.parse(s)
+    }
+}
+
+// 逐位除以进制得到数字串（高位在前）
+fn digits(value: &BigUint, radix: u32, uppercase: bool) -> String {
+    if value.is_zero() {
+        return "0".to_string();
+    }
+    let mut n = value.clone();
+    let mut out = Vec::new();
+    while !n.is_zero() {
+        let d = DIGITS[n.div_rem_small(radix) as usize];
+        out.push(if uppercase { d.to_ascii_uppercase() } else { d });
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+// ---------- 格式化与解析 ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Radix {
+    radix: u32,
+    group: Option<(usize, char)>,
+    uppercase: bool,
+    prefix: bool,
+    min_digits: usize,
+}
+
+impl Radix {
+    pub const BINARY: Radix = Radix::fixed(2);
+    pub const OCTAL: Radix = Radix::fixed(8);
+    pub const DECIMAL: Radix = Radix::fixed(10);
+    pub const HEX: Radix = Radix::fixed(16);
+
+    const fn fixed(radix: u32) -> Radix {
+        Radix { radix, group: None, uppercase: false, prefix: false, min_digits: 0 }
+    }
+
+    pub fn new(radix: u32) -> Result<Radix, RadixError> {
+        if !(2..=36).contains(&radix) {
+            return Err(RadixError::InvalidRadix(radix));
+        }
+        Ok(Radix::fixed(radix))
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    // 从低位起每 size 位插入一个分隔符
+    pub fn group(mut self, size: usize, separator: char) -> Self {
+        self.group = (size > 0).then_some((size, separator));
+        self
+    }
+
+    pub fn uppercase(mut self, on: bool) -> Self {
+        self.uppercase = on;
+        self
+    }
+
+    // 0b / 0o / 0x 前缀，仅对 2、8、16 进制生效
+    pub fn prefix(mut self, on: bool) -> Self {
+        self.prefix = on;
+        self
+    }
+
+    // 不足时左侧补 0
+    pub fn min_digits(mut self, digits: usize) -> Self {
+        self.min_digits = digits;
+        self
+    }
+
+    fn prefix_str(&self) -> &'static str {
+        match self.radix {
+            2 => "0b",
+            8 => "0o",
+            16 => "0x",
+            _ => "",
+        }
+    }
+
+    pub fn format<T: Into<BigInt>>(&self, value: T) -> String {
+        let value = value.into();
+        let sign = if value.negative { "-" } else { "" };
+        self.render(sign, &value.magnitude, self.min_digits)
+    }
+
+    // 补码视图: 负数按 2^bits + value 输出，位数补齐到 bits 位所需的数字个数
+    pub fn twos_complement<T: Into<BigInt>>(&self, value: T, bits: u32) -> Result<String, RadixError> {
+        let value = value.into();
+        let modulus = BigUint::pow2(bits);
+        let out_of_range = RadixError::OutOfRange { bits };
+        let raw = if value.negative {
+            // 最小值为 -2^(bits-1)
+            if bits == 0 || value.magnitude > BigUint::pow2(bits - 1) {
+                return Err(out_of_range);
+            }
+            modulus.sub(&value.magnitude)
+        } else {
+            if value.magnitude >= modulus {
+                return Err(out_of_range);
+            }
+            value.magnitude
+        };
+        let full_width = digits(&modulus.sub(&BigUint::from(1u128)), self.radix, false).len();
+        Ok(self.render("", &raw, self.min_digits.max(full_width)))
+    }
+
+    fn render(&self, sign: &str, magnitude: &BigUint, min_digits: usize) -> String {
+        let mut body = digits(magnitude, self.radix, self.uppercase);
+        if body.len() < min_digits {
+            body.insert_str(0, &"0".repeat(min_digits - body.len()));
+        }
+        if let Some((size, sep)) = self.group {
+            let mut grouped = String::new();
+            for (i, ch) in body.chars().enumerate() {
+                if i > 0 && (body.len() - i).is_multiple_of(size) {
+                    grouped.push(sep);
+                }
+                grouped.push(ch);
+            }
+            body = grouped;
+        }
+        let prefix = if self.prefix { self.prefix_str() } else { "" };
+        format!("{sign}{prefix}{body}")
+    }
+
+    // 接受可选的 +/- 符号、对应进制的前缀，忽略 _ 和分组分隔符
+    pub fn parse(&self, s: &str) -> Result<BigInt, RadixError> {
+        let s = s.trim();
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let prefix = self.prefix_str();
+        let s = match s.get(..prefix.len()) {
+            Some(p) if !prefix.is_empty() && p.eq_ignore_ascii_case(prefix) => &s[prefix.len()..],
+            _ => s,
+        };
+        let separator = self.group.map(|(_, sep)| sep);
+
+        let mut magnitude = BigUint::zero();
+        let mut seen = false;
+        for ch in s.chars() {
+            if ch == '_' || Some(ch) == separator {
+                continue;
+            }
+            let d = ch
+                .to_digit(self.radix)
+                .ok_or(RadixError::InvalidDigit { ch, radix: self.radix })?;
+            magnitude.mul_add_small(self.radix, d);
+            seen = true;
+        }
+        if !seen {
+            return Err(RadixError::Empty);
+        }
+        Ok(BigInt::new(negative, magnitude))
+    }
+
+    // 按 bits 位补码解释: 最高位为 1 时为负数
+    pub fn parse_twos_complement(&self, s: &str, bits: u32) -> Result<BigInt, RadixError> {
+        let raw = self.parse(s)?;
+        if raw.negative || raw.magnitude >= BigUint::pow2(bits) {
+            return Err(RadixError::OutOfRange { bits });
+        }
+        if bits > 0 && raw.magnitude >= BigUint::pow2(bits - 1) {
+            return Ok(BigInt::new(true, BigUint::pow2(bits).sub(&raw.magnitude)));
+        }
+        Ok(raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radix(r: u32) -> Radix {
+        Radix::new(r).unwrap()
+    }
+
+    #[test]
+    fn round_trips_in_common_bases() {
+        let values: [i128; 8] = [0, 1, -1, 35, 255, -4096, 1 << 100, i64::MIN as i128];
+        for r in [2, 8, 16, 36] {
+            for v in values {
+                let text = radix(r).format(v);
+                assert_eq!(radix(r).parse(&text).unwrap().to_i128(), Some(v), "{r} 进制: {text}");
+                let fancy = radix(r).prefix(true).uppercase(true).group(3, '_').min_digits(8).format(v);
+                assert_eq!(radix(r).parse(&fancy).unwrap().to_i128(), Some(v), "{r} 进制: {fancy}");
+            }
+        }
+    }
+
+    #[test]
+    fn known_formats() {
+        assert_eq!(Radix::BINARY.format(10u8), "1010");
+        assert_eq!(Radix::OCTAL.prefix(true).format(8), "0o10");
+        assert_eq!(Radix::HEX.prefix(true).uppercase(true).format(-255), "-0xFF");
+        assert_eq!(radix(36).format(35), "z");
+        assert_eq!(Radix::BINARY.group(4, '_').min_digits(9).format(1), "0_0000_0001");
+        assert_eq!(Radix::DECIMAL.group(3, ',').format(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn extremes_of_i64_and_u64() {
+        assert_eq!(Radix::HEX.format(u64::MAX), "ffffffffffffffff");
+        assert_eq!(Radix::HEX.format(i64::MIN), "-8000000000000000");
+        assert_eq!(Radix::HEX.parse("0xffffffffffffffff").unwrap().to_u128(), Some(u64::MAX as u128));
+        assert_eq!(Radix::DECIMAL.parse(&i64::MIN.to_string()).unwrap().to_i128(), Some(i64::MIN as i128));
+        assert_eq!(Radix::HEX.twos_complement(i64::MIN, 64).unwrap(), "8000000000000000");
+        assert_eq!(Radix::HEX.twos_complement(-1, 64).unwrap(), "ffffffffffffffff");
+        assert_eq!(Radix::HEX.twos_complement(u64::MAX, 64).unwrap(), "ffffffffffffffff");
+        assert_eq!(Radix::HEX.twos_complement(i64::MIN as i128 - 1, 64), Err(RadixError::OutOfRange { bits: 64 }));
+        assert_eq!(Radix::HEX.twos_complement(u64::MAX as u128 + 1, 64), Err(RadixError::OutOfRange { bits: 64 }));
+        let min = Radix::HEX.parse_twos_complement("8000000000000000", 64).unwrap();
+        assert_eq!(min.to_i128(), Some(i64::MIN as i128));
+        assert_eq!(Radix::BINARY.twos_complement(-3, 8).unwrap(), "11111101");
+    }
+
+    #[test]
+    fn prefixes_and_separators_are_optional() {
+        for text in ["0x1f", "0X1F", "1f", "+0x1f", "0x_1_f"] {
+            assert_eq!(Radix::HEX.parse(text).unwrap().to_i128(), Some(31), "{text}");
+        }
+        assert_eq!(Radix::BINARY.parse("-0b1010").unwrap().to_i128(), Some(-10));
+        // 其他进制的前缀不认，0x 中的 x 在 16 进制里不是数字
+        assert_eq!(Radix::BINARY.parse("0x1"), Err(RadixError::InvalidDigit { ch: 'x', radix: 2 }));
+        assert_eq!(Radix::DECIMAL.group(3, ',').parse("1,000").unwrap().to_i128(), Some(1000));
+        assert_eq!(Radix::DECIMAL.parse("1,000"), Err(RadixError::InvalidDigit { ch: ',', radix: 10 }));
+    }
+
+    #[test]
+    fn rejects_invalid_digits_and_radix() {
+        assert_eq!(Radix::BINARY.parse("102"), Err(RadixError::InvalidDigit { ch: '2', radix: 2 }));
+        assert_eq!(Radix::OCTAL.parse("8"), Err(RadixError::InvalidDigit { ch: '8', radix: 8 }));
+        assert_eq!(Radix::HEX.parse("fg"), Err(RadixError::InvalidDigit { ch: 'g', radix: 16 }));
+        assert_eq!(radix(36).parse("z!"), Err(RadixError::InvalidDigit { ch: '!', radix: 36 }));
+        assert_eq!(Radix::new(1), Err(RadixError::InvalidRadix(1)));
+        assert_eq!(Radix::new(37), Err(RadixError::InvalidRadix(37)));
+    }
+
+    #[test]
+    fn rejects_empty_and_sign_only_input() {
+        for text in ["", "   ", "-", "+", "_", "0x", "-0x"] {
+            assert_eq!(Radix::HEX.parse(text), Err(RadixError::Empty), "{text:?}");
+        }
+        // -0 就是 0，不带负号
+        let zero = Radix::DECIMAL.parse("-0").unwrap();
+        assert!(!zero.is_negative());
+        assert_eq!(zero.to_string(), "0");
+    }
+
+    #[test]
+    fn big_uint_bytes_round_trip() {
+        let bytes = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xff];
+        let n = BigUint::from_bytes_be(&bytes);
+        assert_eq!(n.to_bytes_be(), bytes);
+        assert_eq!(n.bits(), 65);
+        assert_eq!(BigUint::from_bytes_be(&[0, 0, 1]).to_bytes_be(), [1]);
+        assert!(BigUint::from_bytes_be(&[0, 0]).is_zero());
+    }
+}
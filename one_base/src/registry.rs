@@ -0,0 +1,398 @@
+// ---------------- 用户注册表 ----------------
+/*
+    what: UserRegistry 管理一组 User: 创建（带校验）、邮箱唯一、登录计数、停用。
+    持久化: 行格式文本，每行一个用户，字段以 \t 分隔:
+        active \t sign_in_count \t username \t email [\t 密码哈希 \t 失败次数 \t 锁定截止]
+    后三个字段是 auth::Credentials，设置过密码的用户才有; v1 文件没有这三个字段，仍可读取。
+    校验规则保证用户名和邮箱中不含 \t 和换行，因此无需转义。
+    第一行是版本头 "# one_base users vN"，不认识的版本拒绝读取，避免旧程序误读新格式。
+    原子写入: 先写入同目录的临时文件并 sync，再 rename 覆盖目标文件，最后 sync 所在目录，
+    保证 rename 本身也落盘; 临时文件名带进程号和序号，多个进程同时保存不会互相覆盖。
+*/
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::auth::{Account, AuthError, Credentials, PasswordHash};
+use crate::user::{User, UserDraft, ValidationError};
+
+const HEADER_PREFIX: &str = "# one_base users v";
+const VERSION: u32 = 2;
+
+// 同一进程内临时文件的序号
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+pub enum RegistryError {
+    Invalid(ValidationError),
+    DuplicateEmail(String),
+    NotFound(String),
+    Inactive(String),
+    NoPassword(String),
+    UnsupportedVersion(String),
+    Auth(AuthError),
+    Io(io::Error),
+    Corrupt { line: usize, reason: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::Invalid(e) => write!(f, "{e}"),
+            RegistryError::DuplicateEmail(email) => write!(f, "邮箱已注册: {email}"),
+            RegistryError::NotFound(email) => write!(f, "用户不存在: {email}"),
+            RegistryError::Inactive(email) => write!(f, "用户已停用: {email}"),
+            RegistryError::NoPassword(email) => write!(f, "用户没有设置密码: {email}"),
+            RegistryError::UnsupportedVersion(header) => write!(f, "不支持的文件版本: {header}"),
+            RegistryError::Auth(e) => write!(f, "{e}"),
+            RegistryError::Io(e) => write!(f, "IO 错误: {e}"),
+            RegistryError::Corrupt { line, reason } => write!(f, "文件第 {line} 行损坏: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<io::Error> for RegistryError {
+    fn from(err: io::Error) -> RegistryError {
+        RegistryError::Io(err)
+    }
+}
+
+impl From<AuthError> for RegistryError {
+    fn from(err: AuthError) -> RegistryError {
+        RegistryError::Auth(err)
+    }
+}
+
+impl From<ValidationError> for RegistryError {
+    fn from(err: ValidationError) -> RegistryError {
+        RegistryError::Invalid(err)
+    }
+}
+
+// 邮箱不区分大小写，统一用小写作为键
+fn key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[derive(Debug, Default)]
+pub struct UserRegistry {
+    users: BTreeMap<String, User>,
+    // 与 users 同一个键，只有设置过密码的用户才有
+    credentials: BTreeMap<String, Credentials>,
+}
+
+impl UserRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    pub fn get(&self, email: &str) -> Option<&User> {
+        self.users.get(&key(email))
+    }
+
+    pub fn create(&mut self, email: &str, username: &str) -> Result<&User, RegistryError> {
+        let user = User::new(email.trim(), username.trim())?;
+        self.insert(user)
+    }
+
+    // 登录成功后调用，返回新的登录次数
+    pub fn record_sign_in(&mut self, email: &str) -> Result<u64, RegistryError> {
+        let user = self.get_active_mut(email)?;
+        user.sign_in_count += 1;
+        Ok(user.sign_in_count)
+    }
+
+    pub fn deactivate(&mut self, email: &str) -> Result<(), RegistryError> {
+        self.get_active_mut(email)?.active = false;
+        Ok(())
+    }
+
+    pub fn set_password(&mut self, email: &str, password: PasswordHash) -> Result<(), RegistryError> {
+        self.get_active_mut(email)?;
+        self.credentials.insert(key(email), Credentials::new(password));
+        Ok(())
+    }
+
+    // 用户连同凭据，交给 Authenticator::sign_in 后用 update_account 写回
+    pub fn account(&self, email: &str) -> Result<Account, RegistryError> {
+        let user = self.get(email).ok_or_else(|| RegistryError::NotFound(email.to_string()))?;
+        let credentials = self.credentials.get(&key(email)).ok_or_else(|| RegistryError::NoPassword(email.to_string()))?;
+        Ok(Account { user: user.clone(), credentials: credentials.clone() })
+    }
+
+    // 写回登录次数和锁定状态; 邮箱是键，不能通过这里修改
+    pub fn update_account(&mut self, account: Account) -> Result<(), RegistryError> {
+        let k = key(&account.user.email);
+        let Some(user) = self.users.get_mut(&k) else {
+            return Err(RegistryError::NotFound(account.user.email));
+        };
+        *user = account.user;
+        self.credentials.insert(k, account.credentials);
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RegistryError> {
+        let path = path.as_ref();
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(format!(".{}.{}.tmp", std::process::id(), TMP_SEQ.fetch_add(1, Ordering::Relaxed)));
+        let tmp = Path::new(&tmp_name);
+
+        // create_new: 万一名字撞上了也不会截断别人的临时文件
+        let file = File::options().write(true).create_new(true).open(tmp)?;
+        let written = self.write_to(file).and_then(|()| fs::rename(tmp, path));
+        if let Err(e) = written {
+            fs::remove_file(tmp).ok();
+            return Err(e.into());
+        }
+        sync_parent(path)?;
+        crate::debug!(path = path.display(), users = self.users.len(); "已保存用户");
+        Ok(())
+    }
+
+    fn write_to(&self, mut file: File) -> io::Result<()> {
+        writeln!(file, "{HEADER_PREFIX}{VERSION}")?;
+        for (k, user) in &self.users {
+            write!(file, "{}\t{}\t{}\t{}", user.active, user.sign_in_count, user.username, user.email)?;
+            if let Some(credentials) = self.credentials.get(k) {
+                write!(file, "\t{}", credentials.encode())?;
+            }
+            writeln!(file)?;
+        }
+        file.sync_all()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines = contents.lines();
+        let version = match lines.next() {
+            Some(header) => parse_version(header)?,
+            None => return Err(RegistryError::Corrupt { line: 1, reason: "缺少文件头".to_string() }),
+        };
+        let mut registry = UserRegistry::new();
+        for (i, line) in lines.enumerate() {
+            let line_no = i + 2;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let corrupt = |reason: String| RegistryError::Corrupt { line: line_no, reason };
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (active, count, username, email, credentials) = match fields[..] {
+                [active, count, username, email] => (active, count, username, email, None),
+                [active, count, username, email, hash, failed, locked] if version >= 2 => {
+                    let credentials =
+                        Credentials::decode(&[hash, failed, locked].join("\t")).map_err(|e| corrupt(e.to_string()))?;
+                    (active, count, username, email, Some(credentials))
+                }
+                _ if version < 2 => return Err(corrupt(format!("需要 4 个字段，实际 {}", fields.len()))),
+                _ => return Err(corrupt(format!("需要 4 或 7 个字段，实际 {}", fields.len()))),
+            };
+            let mut draft = UserDraft::new();
+            for (field, value) in [("email", email), ("username", username), ("active", active), ("sign_in_count", count)] {
+                draft.set(field, value).map_err(|e| corrupt(e.to_string()))?;
+            }
+            let user = draft.build().map_err(|e| corrupt(e.to_string()))?;
+            let k = key(&user.email);
+            registry.insert(user).map_err(|e| corrupt(e.to_string()))?;
+            if let Some(credentials) = credentials {
+                registry.credentials.insert(k, credentials);
+            }
+        }
+        crate::debug!(users = registry.users.len(); "已加载用户");
+        Ok(registry)
+    }
+
+    fn insert(&mut self, user: User) -> Result<&User, RegistryError> {
+        let k = key(&user.email);
+        if self.users.contains_key(&k) {
+            return Err(RegistryError::DuplicateEmail(user.email));
+        }
+        Ok(self.users.entry(k).or_insert(user))
+    }
+
+    fn get_active_mut(&mut self, email: &str) -> Result<&mut User, RegistryError> {
+        match self.users.get_mut(&key(email)) {
+            None => Err(RegistryError::NotFound(email.to_string())),
+            Some(user) if !user.active => Err(RegistryError::Inactive(email.to_string())),
+            Some(user) => Ok(user),
+        }
+    }
+}
+
+// 文件头 "# one_base users vN" -> N，只接受 1..=VERSION
+fn parse_version(header: &str) -> Result<u32, RegistryError> {
+    let Some(version) = header.trim_end().strip_prefix(HEADER_PREFIX) else {
+        return Err(RegistryError::Corrupt { line: 1, reason: format!("缺少文件头: {header}") });
+    };
+    match version.parse::<u32>() {
+        Ok(v @ 1..=VERSION) => Ok(v),
+        _ => Err(RegistryError::UnsupportedVersion(header.to_string())),
+    }
+}
+
+// rename 只修改了目录项，要 sync 目录才能保证断电后仍然可见;
+// Windows 不能以这种方式打开目录，只在 Unix 上执行
+fn sync_parent(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::MIN_ITERATIONS;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    // 每个测试用自己的目录，并行运行时互不干扰
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("one_base_registry_{name}_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn create_validates_fields() {
+        let mut registry = UserRegistry::new();
+        assert!(matches!(registry.create("not-an-email", "sui"), Err(RegistryError::Invalid(ValidationError::Email(_)))));
+        assert!(matches!(registry.create("sui@example", "sui"), Err(RegistryError::Invalid(ValidationError::Email(_)))));
+        assert!(matches!(registry.create("sui@example.com", "s"), Err(RegistryError::Invalid(ValidationError::Username(_)))));
+        assert!(matches!(
+            registry.create("sui@example.com", "sui sonic"),
+            Err(RegistryError::Invalid(ValidationError::Username(_)))
+        ));
+        assert!(registry.is_empty());
+
+        // 首尾空白被去掉
+        let user = registry.create("  sui@example.com ", " sui ").unwrap();
+        assert_eq!((user.email.as_str(), user.username.as_str()), ("sui@example.com", "sui"));
+        assert_eq!(user.sign_in_count, 0);
+        assert!(user.active);
+    }
+
+    #[test]
+    fn emails_are_unique_ignoring_case() {
+        let mut registry = UserRegistry::new();
+        registry.create("Sui@Example.com", "sui").unwrap();
+        assert!(matches!(
+            registry.create("sui@example.COM", "other"),
+            Err(RegistryError::DuplicateEmail(email)) if email == "sui@example.COM"
+        ));
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.get("SUI@example.com").unwrap().username, "sui");
+    }
+
+    #[test]
+    fn sign_in_counts_and_deactivation() {
+        let mut registry = UserRegistry::new();
+        registry.create("sui@example.com", "sui").unwrap();
+        assert_eq!(registry.record_sign_in("sui@example.com").unwrap(), 1);
+        assert_eq!(registry.record_sign_in("SUI@example.com").unwrap(), 2);
+        assert_eq!(registry.get("sui@example.com").unwrap().sign_in_count, 2);
+        assert!(matches!(registry.record_sign_in("nobody@example.com"), Err(RegistryError::NotFound(_))));
+
+        registry.deactivate("sui@example.com").unwrap();
+        assert!(matches!(registry.record_sign_in("sui@example.com"), Err(RegistryError::Inactive(_))));
+        assert!(matches!(registry.deactivate("sui@example.com"), Err(RegistryError::Inactive(_))));
+        let hash = PasswordHash::new("correct horse", MIN_ITERATIONS).unwrap();
+        assert!(matches!(registry.set_password("sui@example.com", hash), Err(RegistryError::Inactive(_))));
+        // 停用后登录次数保持不变，并随文件保存
+        let dir = temp_dir("deactivate");
+        let path = dir.join("users.txt");
+        registry.save(&path).unwrap();
+        let user = UserRegistry::load(&path).unwrap().get("sui@example.com").cloned().unwrap();
+        assert_eq!((user.active, user.sign_in_count), (false, 2));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn credentials_are_saved_with_users() {
+        let dir = temp_dir("credentials");
+        let path = dir.join("users.txt");
+        let mut registry = UserRegistry::new();
+        registry.create("sui@example.com", "sui").unwrap();
+        registry.create("sonic@example.com", "sonic").unwrap();
+        let hash = PasswordHash::new("correct horse", MIN_ITERATIONS).unwrap();
+        registry.set_password("sui@example.com", hash).unwrap();
+
+        let mut account = registry.account("sui@example.com").unwrap();
+        account.credentials.failed_attempts = 5;
+        account.credentials.locked_until = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_060));
+        registry.update_account(account.clone()).unwrap();
+        registry.save(&path).unwrap();
+
+        let loaded = UserRegistry::load(&path).unwrap();
+        assert_eq!(loaded.account("sui@example.com").unwrap().credentials, account.credentials);
+        assert!(matches!(loaded.account("sonic@example.com"), Err(RegistryError::NoPassword(_))));
+        assert_eq!(loaded.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_saves_do_not_share_a_temp_file() {
+        let dir = temp_dir("concurrent");
+        let path = dir.join("users.txt");
+        let mut registry = UserRegistry::new();
+        for i in 0..50 {
+            registry.create(&format!("user{i}@example.com"), &format!("user{i}")).unwrap();
+        }
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| registry.save(&path).unwrap());
+            }
+        });
+        assert_eq!(UserRegistry::load(&path).unwrap().len(), 50);
+        // 临时文件都已经 rename 或清理掉
+        let leftovers: Vec<_> = fs::read_dir(&dir).unwrap().filter(|e| e.as_ref().unwrap().path() != path).collect();
+        assert!(leftovers.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_checks_the_header_version() {
+        let dir = temp_dir("version");
+        let path = dir.join("users.txt");
+
+        fs::write(&path, "# one_base users v1\ntrue\t3\tsui\tsui@example.com\n").unwrap();
+        assert_eq!(UserRegistry::load(&path).unwrap().get("sui@example.com").unwrap().sign_in_count, 3);
+
+        fs::write(&path, "# one_base users v1\ntrue\t3\tsui\tsui@example.com\tx\t0\t-\n").unwrap();
+        assert!(matches!(UserRegistry::load(&path), Err(RegistryError::Corrupt { line: 2, .. })));
+
+        fs::write(&path, "# one_base users v3\ntrue\t3\tsui\tsui@example.com\n").unwrap();
+        assert!(matches!(UserRegistry::load(&path), Err(RegistryError::UnsupportedVersion(_))));
+
+        fs::write(&path, "true\t3\tsui\tsui@example.com\n").unwrap();
+        assert!(matches!(UserRegistry::load(&path), Err(RegistryError::Corrupt { line: 1, .. })));
+
+        fs::write(&path, "").unwrap();
+        assert!(matches!(UserRegistry::load(&path), Err(RegistryError::Corrupt { line: 1, .. })));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
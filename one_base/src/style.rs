@@ -0,0 +1,302 @@
+// ---------------- 终端样式 ----------------
+/*
+    what: 前景色、背景色、粗体、下划线，供各个命令行工具共用。
+    how:
+        - Style 链式构造: Style::new().fg(Color::rgb(255, 0, 0)).bold();
+        - style.paint("文本") 得到 Styled，可以继续 push 文本或 nest 内层样式;
+          内层结束后自动恢复外层样式，不会把外层的颜色一起重置掉;
+        - ColorMode 决定输出: Never 输出纯文本，Ansi256 / TrueColor 输出转义序列;
+          ColorMode::detect(stream): 设置了 NO_COLOR、或该输出流不是终端时为 Never，
+          COLORTERM=truecolor/24bit 时为 TrueColor，否则 Ansi256;
+        - stdout 和 stderr 分别检测: prog 2>log 时 stdout 仍有颜色，log 中没有转义序列;
+        - Display 按 stdout 的检测结果输出，写到 stderr 时用 styled.for_stderr();
+          render(mode) 用于写入缓冲区、测试输出;
+        - width(): 显示宽度，不计转义序列（见 width.rs）;
+*/
+
+use std::env;
+use std::fmt::{self, Write};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+use crate::color::Color;
+use crate::width::{self, str_width};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorMode {
+    #[default]
+    Never,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorMode {
+    pub fn detect(stream: Stream) -> ColorMode {
+        let is_tty = match stream {
+            Stream::Stdout => std::io::stdout().is_terminal(),
+            Stream::Stderr => std::io::stderr().is_terminal(),
+        };
+        Self::from_env(
+            env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            is_tty,
+            env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    // 检测规则，拆出来便于在没有终端的环境里验证
+    pub fn from_env(no_color: bool, is_tty: bool, colorterm: Option<&str>) -> ColorMode {
+        if no_color || !is_tty {
+            ColorMode::Never
+        } else if matches!(colorterm, Some("truecolor" | "24bit")) {
+            ColorMode::TrueColor
+        } else {
+            ColorMode::Ansi256
+        }
+    }
+
+    // 首次调用时检测，之后沿用
+    pub fn stdout() -> ColorMode {
+        static MODE: OnceLock<ColorMode> = OnceLock::new();
+        *MODE.get_or_init(|| ColorMode::detect(Stream::Stdout))
+    }
+
+    pub fn stderr() -> ColorMode {
+        static MODE: OnceLock<ColorMode> = OnceLock::new();
+        *MODE.get_or_init(|| ColorMode::detect(Stream::Stderr))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    underline: bool,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Style { fg: None, bg: None, bold: false, underline: false }
+    }
+
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::new()
+    }
+
+    // 内层没有设置的属性沿用外层
+    fn inherit(self, outer: Style) -> Style {
+        Style {
+            fg: self.fg.or(outer.fg),
+            bg: self.bg.or(outer.bg),
+            bold: self.bold || outer.bold,
+            underline: self.underline || outer.underline,
+        }
+    }
+
+    pub fn paint(self, text: impl Into<String>) -> Styled {
+        Styled { style: self, parts: vec![Part::Text(text.into())] }
+    }
+
+    // SGR 转义序列，如 \x1b[1;38;5;196m
+    fn write_prefix(&self, out: &mut impl Write, mode: ColorMode) -> fmt::Result {
+        if mode == ColorMode::Never || self.is_plain() {
+            return Ok(());
+        }
+        let mut codes: Vec<String> = Vec::new();
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.underline {
+            codes.push("4".to_string());
+        }
+        for (base, color) in [(38, self.fg), (48, self.bg)] {
+            match (color, mode) {
+                (Some(c), ColorMode::TrueColor) => codes.push(format!("{base};2;{};{};{}", c.r, c.g, c.b)),
+                (Some(c), _) => codes.push(format!("{base};5;{}", c.nearest_ansi256())),
+                (None, _) => {}
+            }
+        }
+        write!(out, "\x1b[{}m", codes.join(";"))
+    }
+}
+
+// 常用样式
+pub const ERROR: Style = Style::new().fg(Color::rgb(215, 0, 0)).bold();
+pub const WARNING: Style = Style::new().fg(Color::rgb(215, 135, 0));
+pub const SUCCESS: Style = Style::new().fg(Color::rgb(0, 175, 0));
+pub const DIM: Style = Style::new().fg(Color::rgb(128, 128, 128));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Nested(Styled),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Styled {
+    style: Style,
+    parts: Vec<Part>,
+}
+
+impl Styled {
+    pub fn push(mut self, text: impl Into<String>) -> Self {
+        self.parts.push(Part::Text(text.into()));
+        self
+    }
+
+    pub fn nest(mut self, inner: Styled) -> Self {
+        self.parts.push(Part::Nested(inner));
+        self
+    }
+
+    // 去掉样式后的文本
+    pub fn plain(&self) -> String {
+        self.render(ColorMode::Never)
+    }
+
+    pub fn width(&self) -> usize {
+        str_width(&self.plain())
+    }
+
+    // 按 stderr 的检测结果输出: eprintln!("{}", styled.for_stderr())
+    pub fn for_stderr(&self) -> ForStderr<'_> {
+        ForStderr(self)
+    }
+
+    pub fn render(&self, mode: ColorMode) -> String {
+        let mut out = String::new();
+        // 写入 String 不会失败
+        let _ = self.render_to(&mut out, mode);
+        out
+    }
+
+    pub fn render_to(&self, out: &mut impl Write, mode: ColorMode) -> fmt::Result {
+        self.render_within(out, mode, Style::new())
+    }
+
+    fn render_within(&self, out: &mut impl Write, mode: ColorMode, outer: Style) -> fmt::Result {
+        let style = self.style.inherit(outer);
+        let colored = mode != ColorMode::Never && !style.is_plain();
+        style.write_prefix(out, mode)?;
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.write_str(text)?,
+                Part::Nested(inner) => {
+                    inner.render_within(out, mode, style)?;
+                    // 内层以重置结尾，重新写入外层样式
+                    style.write_prefix(out, mode)?;
+                }
+            }
+        }
+        if colored {
+            out.write_str("\x1b[0m")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Styled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render_to(f, ColorMode::stdout())
+    }
+}
+
+pub struct ForStderr<'a>(&'a Styled);
+
+impl fmt::Display for ForStderr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.render_to(f, ColorMode::stderr())
+    }
+}
+
+// 去掉字符串中的 ANSI 转义序列
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            width::skip_escape(&mut chars);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Style = Style::new().fg(Color::rgb(255, 0, 0));
+
+    #[test]
+    fn from_env_detection_rules() {
+        assert_eq!(ColorMode::from_env(false, true, None), ColorMode::Ansi256);
+        assert_eq!(ColorMode::from_env(false, true, Some("truecolor")), ColorMode::TrueColor);
+        assert_eq!(ColorMode::from_env(false, true, Some("24bit")), ColorMode::TrueColor);
+        assert_eq!(ColorMode::from_env(false, true, Some("yes")), ColorMode::Ansi256);
+        // NO_COLOR 和非终端优先于 COLORTERM
+        assert_eq!(ColorMode::from_env(true, true, Some("truecolor")), ColorMode::Never);
+        assert_eq!(ColorMode::from_env(false, false, Some("truecolor")), ColorMode::Never);
+        assert_eq!(ColorMode::from_env(true, false, None), ColorMode::Never);
+    }
+
+    #[test]
+    fn nested_styles_restore_outer_after_reset() {
+        let styled = RED.paint("a").nest(Style::new().underline().paint("b")).push("c");
+        assert_eq!(
+            styled.render(ColorMode::Ansi256),
+            "\x1b[38;5;196ma\x1b[4;38;5;196mb\x1b[0m\x1b[38;5;196mc\x1b[0m"
+        );
+        assert_eq!(
+            styled.render(ColorMode::TrueColor),
+            "\x1b[38;2;255;0;0ma\x1b[4;38;2;255;0;0mb\x1b[0m\x1b[38;2;255;0;0mc\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn never_mode_outputs_plain_text() {
+        let styled = ERROR.paint("错误: ").nest(DIM.bg(Color::WHITE).paint("详情")).push("!");
+        assert_eq!(styled.render(ColorMode::Never), "错误: 详情!");
+        assert_eq!(styled.plain(), "错误: 详情!");
+        assert_eq!(styled.width(), 11);
+        // 没有样式时也不输出转义序列
+        assert_eq!(Style::new().paint("x").render(ColorMode::TrueColor), "x");
+    }
+
+    #[test]
+    fn strip_ansi_removes_escape_sequences() {
+        let styled = ERROR.paint("a").nest(SUCCESS.bold().paint("b")).push("c");
+        let rendered = styled.render(ColorMode::TrueColor);
+        assert_ne!(rendered, "abc");
+        assert_eq!(strip_ansi(&rendered), "abc");
+        assert_eq!(strip_ansi("\x1b[1mbold\x1b[0m 中文"), "bold 中文");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}
@@ -0,0 +1,261 @@
+// ---------------- 表格输出 ----------------
+/*
+    what: 把一组结构体打印成对齐的表格，补充 {:?} / {:#?} 之外的展示方式。
+    how:
+        - Table::new(表头) 之后用链式方法设置对齐、最大列宽和边框样式;
+        - 列宽按显示宽度计算（width 模块），中文占 2 列，不会错位;
+        - 超过最大列宽的单元格截断并以 … 结尾;
+        - 样式: Ascii（+-|）、Box（┌─┐）、Markdown;
+        - 实现 TableRow 的类型可以直接 Table::from_rows(&vec);
+*/
+
+use std::fmt;
+
+use crate::width::{expand_tabs, str_width, truncate};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    #[default]
+    Ascii,
+    Box,
+    Markdown,
+}
+
+// 可以作为表格一行的类型
+pub trait TableRow {
+    fn headers() -> Vec<String>;
+    fn cells(&self) -> Vec<String>;
+}
+
+#[derive(Debug, Clone, Default)]
+struct Column {
+    header: String,
+    align: Align,
+    max_width: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    style: Style,
+}
+
+// 边框字符: (左, 填充, 交叉, 右)
+struct Rule(char, char, char, char);
+
+impl Style {
+    fn rules(self) -> [Rule; 3] {
+        match self {
+            Style::Ascii | Style::Markdown => {
+                [Rule('+', '-', '+', '+'), Rule('+', '-', '+', '+'), Rule('+', '-', '+', '+')]
+            }
+            Style::Box => [Rule('┌', '─', '┬', '┐'), Rule('├', '─', '┼', '┤'), Rule('└', '─', '┴', '┘')],
+        }
+    }
+
+    fn vertical(self) -> char {
+        match self {
+            Style::Box => '│',
+            Style::Ascii | Style::Markdown => '|',
+        }
+    }
+}
+
+impl Table {
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let columns = headers
+            .into_iter()
+            .map(|h| Column { header: h.into(), ..Column::default() })
+            .collect();
+        Table { columns, ..Table::default() }
+    }
+
+    pub fn from_rows<T: TableRow>(rows: &[T]) -> Self {
+        let mut table = Table::new(T::headers());
+        for row in rows {
+            table.push_row(row.cells());
+        }
+        table
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        if let Some(c) = self.columns.get_mut(column) {
+            c.align = align;
+        }
+        self
+    }
+
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        if let Some(c) = self.columns.get_mut(column) {
+            c.max_width = Some(width);
+        }
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    // 缺少的单元格补空，多余的忽略
+    pub fn push_row<I, S>(&mut self, cells: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut row: Vec<String> = cells.into_iter().map(Into::into).take(self.columns.len()).collect();
+        row.resize(self.columns.len(), String::new());
+        self.rows.push(row);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    // 单元格内容: 换行替换为空格，制表符展开，Markdown 先转义 | 再按最大列宽截断
+    fn cell_text(&self, column: &Column, raw: &str) -> String {
+        let text = expand_tabs(&raw.replace(['\r', '\n'], " "));
+        let text = match self.style {
+            Style::Markdown => text.replace('|', "\\|"),
+            _ => text,
+        };
+        match column.max_width {
+            Some(max) => truncate_escaped(&text, max),
+            None => text,
+        }
+    }
+
+    fn widths(&self, header: &[String], body: &[Vec<String>]) -> Vec<usize> {
+        (0..self.columns.len())
+            .map(|i| {
+                let min = if self.style == Style::Markdown { 3 } else { 1 };
+                body.iter()
+                    .map(|r| str_width(&r[i]))
+                    .chain([str_width(&header[i]), min])
+                    .max()
+                    .unwrap_or(min)
+            })
+            .collect()
+    }
+}
+
+// 截断点正好落在 \| 中间时去掉孤立的反斜杠，否则它会转义后面的 …
+fn truncate_escaped(text: &str, max: usize) -> String {
+    let mut cut = truncate(text, max);
+    let kept: usize = cut.chars().zip(text.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum();
+    if text[..kept].ends_with('\\') && text[kept..].starts_with('|') {
+        cut.remove(kept - 1);
+    }
+    cut
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let gap = width.saturating_sub(str_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, gap),
+        Align::Right => (gap, 0),
+        Align::Center => (gap / 2, gap - gap / 2),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header: Vec<String> = self.columns.iter().map(|c| self.cell_text(c, &c.header)).collect();
+        let body: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| self.columns.iter().zip(row).map(|(c, cell)| self.cell_text(c, cell)).collect())
+            .collect();
+        let widths = self.widths(&header, &body);
+
+        let v = self.style.vertical();
+        let line = |f: &mut fmt::Formatter, cells: &[String]| -> fmt::Result {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((text, col), &w)| pad(text, w, col.align))
+                .collect();
+            writeln!(f, "{v} {} {v}", padded.join(&format!(" {v} ")))
+        };
+        let rule = |f: &mut fmt::Formatter, r: &Rule| -> fmt::Result {
+            let segments: Vec<String> = widths.iter().map(|&w| r.1.to_string().repeat(w + 2)).collect();
+            writeln!(f, "{}{}{}", r.0, segments.join(&r.2.to_string()), r.3)
+        };
+
+        if self.style == Style::Markdown {
+            line(f, &header)?;
+            let markers: Vec<String> = self
+                .columns
+                .iter()
+                .zip(&widths)
+                .map(|(col, &w)| match col.align {
+                    Align::Left => format!(":{}", "-".repeat(w - 1)),
+                    Align::Right => format!("{}:", "-".repeat(w - 1)),
+                    Align::Center => format!(":{}:", "-".repeat(w - 2)),
+                })
+                .collect();
+            writeln!(f, "| {} |", markers.join(" | "))?;
+            return body.iter().try_for_each(|row| line(f, row));
+        }
+
+        let [top, middle, bottom] = self.style.rules();
+        rule(f, &top)?;
+        line(f, &header)?;
+        rule(f, &middle)?;
+        for row in &body {
+            line(f, row)?;
+        }
+        rule(f, &bottom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_cells_stay_within_max_width() {
+        let mut table = Table::new(["名称"]).style(Style::Markdown).max_width(0, 6);
+        table.push_row(["a|b|c|d"]);
+        let out = table.render();
+        let row = out.lines().nth(2).unwrap();
+        // 转义之后再截断: a\|b\| 是 6 列，只能放下 a\|b 和 …
+        assert_eq!(row, "| a\\|b… |");
+        let cell = row.trim_matches('|').trim();
+        assert!(str_width(cell) <= 6);
+    }
+
+    #[test]
+    fn markdown_truncation_never_leaves_a_dangling_backslash() {
+        let mut table = Table::new(["x"]).style(Style::Markdown).max_width(0, 3);
+        table.push_row(["a|b"]);
+        let row = table.render().lines().nth(2).unwrap().to_string();
+        assert_eq!(row, "| a…  |");
+    }
+
+    #[test]
+    fn tabs_are_expanded_before_measuring() {
+        let mut table = Table::new(["k", "v"]);
+        table.push_row(["a\tb", "1"]);
+        let out = table.render();
+        let widths: Vec<usize> = out.lines().map(str_width).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]), "{out}");
+        assert!(out.contains("| a       b | 1 |"), "{out}");
+    }
+}
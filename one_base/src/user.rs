@@ -0,0 +1,157 @@
+// ---------------- 用户 User ----------------
+// 来自结构体课程的 User / build_user，增加字段校验
+
+use std::fmt;
+
+use crate::table::TableRow;
+
+pub mod builder;
+
+pub use builder::{DraftError, UserBuilder, UserDraft};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub active: bool,
+    pub username: String,
+    pub email: String,
+    pub sign_in_count: u64,
+}
+
+// 课程原函数: 字段初始化简写
+pub fn build_user(email: String, username: String) -> User {
+    User {
+        active: true,
+        email,
+        username,
+        sign_in_count: 1,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Email(String),
+    Username(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::Email(reason) => write!(f, "邮箱无效: {reason}"),
+            ValidationError::Username(reason) => write!(f, "用户名无效: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// 邮箱: local@domain，域名至少两段，不含空白字符
+pub fn validate_email(email: &str) -> Result<(), ValidationError> {
+    let err = |reason: &str| Err(ValidationError::Email(format!("{email:?} {reason}")));
+    if email.len() > 254 {
+        return err("超过 254 字节");
+    }
+    if email.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return err("包含空白或控制字符");
+    }
+    let Some((local, domain)) = email.split_once('@') else {
+        return err("缺少 @");
+    };
+    if local.is_empty() || domain.contains('@') {
+        return err("@ 前为空或包含多个 @");
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|l| l.is_empty() || l.starts_with('-') || l.ends_with('-')) {
+        return err("域名格式错误");
+    }
+    Ok(())
+}
+
+// 用户名: 2~32 个字符，字母数字（含中文）、下划线、连字符、点
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let err = |reason: &str| Err(ValidationError::Username(format!("{username:?} {reason}")));
+    let len = username.chars().count();
+    if !(2..=32).contains(&len) {
+        return err("长度需在 2~32 个字符之间");
+    }
+    if !username.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        return err("只能包含字母、数字、_ - .");
+    }
+    Ok(())
+}
+
+impl User {
+    // 带校验的构造函数，新用户尚未登录过，sign_in_count 从 0 开始（UserBuilder 的默认值）
+    pub fn new(email: &str, username: &str) -> Result<User, ValidationError> {
+        UserBuilder::new().email(email).username(username).build_validated()
+    }
+}
+
+// Table::from_rows(&users) 打印用户列表
+impl TableRow for User {
+    fn headers() -> Vec<String> {
+        ["用户名", "邮箱", "启用", "登录次数"].map(String::from).to_vec()
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.username.clone(),
+            self.email.clone(),
+            if self.active { "是" } else { "否" }.to_string(),
+            self.sign_in_count.to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email_err(email: &str) -> bool {
+        matches!(validate_email(email), Err(ValidationError::Email(_)))
+    }
+
+    fn username_err(username: &str) -> bool {
+        matches!(validate_username(username), Err(ValidationError::Username(_)))
+    }
+
+    #[test]
+    fn valid_emails() {
+        for email in ["sui@example.com", "a.b+tag@mail.example.co", "用户@例子.中国", "x@a-b.io"] {
+            assert_eq!(validate_email(email), Ok(()), "{email}");
+        }
+    }
+
+    #[test]
+    fn malformed_emails() {
+        let too_long = format!("{}@example.com", "a".repeat(250));
+        for email in [
+            "", "sui", "sui@", "@example.com", "sui@@example.com", "a@b@example.com", "sui@localhost",
+            "sui@example..com", "sui@.example.com", "sui@example.com.", "sui@-example.com", "sui@example-.com",
+            "sui @example.com", "sui@example.com\n", "sui\t@example.com", &too_long,
+        ] {
+            assert!(email_err(email), "{email:?} 应当无效");
+        }
+    }
+
+    #[test]
+    fn username_rules() {
+        for name in ["su", "sui_sonic", "a-b.c", "用户", &"x".repeat(32)] {
+            assert_eq!(validate_username(name), Ok(()), "{name}");
+        }
+        for name in ["", "s", "用", &"x".repeat(33), "sui sonic", "sui!", "a/b", "tab\there"] {
+            assert!(username_err(name), "{name:?} 应当无效");
+        }
+        // 长度按字符计，不按字节
+        assert_eq!(validate_username(&"中".repeat(32)), Ok(()));
+    }
+
+    #[test]
+    fn new_validates_and_starts_at_zero() {
+        let user = User::new("sui@example.com", "sui").unwrap();
+        assert_eq!(user, User { active: true, username: "sui".into(), email: "sui@example.com".into(), sign_in_count: 0 });
+        assert!(matches!(User::new("bad", "sui"), Err(ValidationError::Email(_))));
+        assert!(matches!(User::new("sui@example.com", "x"), Err(ValidationError::Username(_))));
+        // 邮箱先于用户名检查
+        assert!(matches!(User::new("bad", "x"), Err(ValidationError::Email(_))));
+    }
+}
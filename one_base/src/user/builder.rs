@@ -0,0 +1,253 @@
+// ---------------- UserBuilder ----------------
+/*
+    what: 用具名方法代替 build_user 的位置参数。
+    how:
+        - UserBuilder<E, U>: 类型参数记录 email / username 是否已设置（Missing / Provided），
+          只有 UserBuilder<Provided, Provided> 才有 build()，漏写必填字段在编译期报错:
+              UserBuilder::new().email("a@b.com").build();
+              // error[E0599]: no method named `build` found for struct `UserBuilder<Provided, Missing>`
+          UserBuilder 上的 compile_fail 文档测试（cargo test --doc）确保这一点不会退化;
+          active 默认 true，sign_in_count 默认 0;
+        - UserDraft: 字段都是 Option，按名称逐个设置，build() 时才检查缺失并校验，
+          用于从文件等运行时数据构造;
+    why: 大一些的配置结构体也按这个模式: 必填字段用类型参数，可选字段给默认值。
+*/
+
+use std::fmt;
+
+use super::{validate_email, validate_username, User, ValidationError};
+
+// 必填字段的两种状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Missing;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provided(String);
+
+/// 必填字段都设置后才能 build:
+///
+/// ```
+/// use one_base::user::UserBuilder;
+///
+/// let user = UserBuilder::new().username("sui").email("sui@example.com").active(false).build();
+/// assert_eq!(user.email, "sui@example.com");
+/// assert!(!user.active);
+/// ```
+///
+/// 缺少 username 时没有 build():
+///
+/// ```compile_fail,E0599
+/// use one_base::user::UserBuilder;
+///
+/// let user = UserBuilder::new().email("sui@example.com").build();
+/// ```
+///
+/// 缺少 email:
+///
+/// ```compile_fail,E0599
+/// use one_base::user::UserBuilder;
+///
+/// let user = UserBuilder::new().username("sui").sign_in_count(3).build_validated();
+/// ```
+///
+/// 两个都没有:
+///
+/// ```compile_fail,E0599
+/// use one_base::user::UserBuilder;
+///
+/// let user = UserBuilder::new().active(true).build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserBuilder<E, U> {
+    email: E,
+    username: U,
+    active: bool,
+    sign_in_count: u64,
+}
+
+impl UserBuilder<Missing, Missing> {
+    pub fn new() -> Self {
+        UserBuilder { email: Missing, username: Missing, active: true, sign_in_count: 0 }
+    }
+}
+
+impl Default for UserBuilder<Missing, Missing> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, U> UserBuilder<E, U> {
+    // 重复设置时以最后一次为准
+    pub fn email(self, email: impl Into<String>) -> UserBuilder<Provided, U> {
+        UserBuilder {
+            email: Provided(email.into()),
+            username: self.username,
+            active: self.active,
+            sign_in_count: self.sign_in_count,
+        }
+    }
+
+    pub fn username(self, username: impl Into<String>) -> UserBuilder<E, Provided> {
+        UserBuilder {
+            email: self.email,
+            username: Provided(username.into()),
+            active: self.active,
+            sign_in_count: self.sign_in_count,
+        }
+    }
+
+    pub fn active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+
+    pub fn sign_in_count(mut self, count: u64) -> Self {
+        self.sign_in_count = count;
+        self
+    }
+}
+
+impl UserBuilder<Provided, Provided> {
+    // 与 build_user 一样不做校验，适用于程序内部已知合法的数据
+    pub fn build(self) -> User {
+        User {
+            active: self.active,
+            username: self.username.0,
+            email: self.email.0,
+            sign_in_count: self.sign_in_count,
+        }
+    }
+
+    // 字段齐全但内容来自外部时使用
+    pub fn build_validated(self) -> Result<User, ValidationError> {
+        validate_email(&self.email.0)?;
+        validate_username(&self.username.0)?;
+        Ok(self.build())
+    }
+}
+
+// ---------- 运行时校验的版本 ----------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DraftError {
+    Missing(&'static str),
+    UnknownField(String),
+    BadValue { field: &'static str, value: String },
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for DraftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DraftError::Missing(field) => write!(f, "缺少必填字段: {field}"),
+            DraftError::UnknownField(field) => write!(f, "未知字段: {field}"),
+            DraftError::BadValue { field, value } => write!(f, "字段 {field} 的值无效: {value}"),
+            DraftError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DraftError {}
+
+impl From<ValidationError> for DraftError {
+    fn from(e: ValidationError) -> Self {
+        DraftError::Invalid(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UserDraft {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub active: Option<bool>,
+    pub sign_in_count: Option<u64>,
+}
+
+impl UserDraft {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 按字段名设置，值为文本，如配置文件中的 key = value
+    pub fn set(&mut self, field: &str, value: &str) -> Result<(), DraftError> {
+        let bad = |field: &'static str| DraftError::BadValue { field, value: value.to_string() };
+        match field {
+            "email" => self.email = Some(value.to_string()),
+            "username" => self.username = Some(value.to_string()),
+            "active" => self.active = Some(value.parse().map_err(|_| bad("active"))?),
+            "sign_in_count" => self.sign_in_count = Some(value.parse().map_err(|_| bad("sign_in_count"))?),
+            _ => return Err(DraftError::UnknownField(field.to_string())),
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<User, DraftError> {
+        let email = self.email.ok_or(DraftError::Missing("email"))?;
+        let username = self.username.ok_or(DraftError::Missing("username"))?;
+        let mut builder = UserBuilder::new().email(email).username(username);
+        if let Some(active) = self.active {
+            builder = builder.active(active);
+        }
+        if let Some(count) = self.sign_in_count {
+            builder = builder.sign_in_count(count);
+        }
+        Ok(builder.build_validated()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(fields: &[(&str, &str)]) -> Result<User, DraftError> {
+        let mut draft = UserDraft::new();
+        for (field, value) in fields {
+            draft.set(field, value)?;
+        }
+        draft.build()
+    }
+
+    #[test]
+    fn builder_defaults_and_overrides() {
+        let user = UserBuilder::new().email("old@example.com").username("sui").email("sui@example.com").build();
+        assert_eq!(user, User { active: true, username: "sui".into(), email: "sui@example.com".into(), sign_in_count: 0 });
+        let user = UserBuilder::new().username("sui").email("sui@example.com").active(false).sign_in_count(7).build();
+        assert_eq!((user.active, user.sign_in_count), (false, 7));
+    }
+
+    #[test]
+    fn build_validated_rejects_bad_fields() {
+        let bad_email = UserBuilder::new().email("nope").username("sui").build_validated();
+        assert!(matches!(bad_email, Err(ValidationError::Email(_))));
+        let bad_name = UserBuilder::new().email("sui@example.com").username("s").build_validated();
+        assert!(matches!(bad_name, Err(ValidationError::Username(_))));
+        // build 不校验
+        assert_eq!(UserBuilder::new().email("nope").username("s").build().email, "nope");
+    }
+
+    #[test]
+    fn draft_builds_from_text_fields() {
+        let user = draft(&[("username", "sui"), ("email", "sui@example.com"), ("active", "false"), ("sign_in_count", "3")])
+            .unwrap();
+        assert_eq!(user, User { active: false, username: "sui".into(), email: "sui@example.com".into(), sign_in_count: 3 });
+        let user = draft(&[("email", "sui@example.com"), ("username", "sui")]).unwrap();
+        assert_eq!((user.active, user.sign_in_count), (true, 0));
+    }
+
+    #[test]
+    fn draft_reports_each_error() {
+        assert_eq!(draft(&[("username", "sui")]), Err(DraftError::Missing("email")));
+        assert_eq!(draft(&[("email", "sui@example.com")]), Err(DraftError::Missing("username")));
+        assert_eq!(draft(&[("nickname", "sui")]), Err(DraftError::UnknownField("nickname".into())));
+        assert_eq!(draft(&[("active", "yes")]), Err(DraftError::BadValue { field: "active", value: "yes".into() }));
+        assert_eq!(
+            draft(&[("sign_in_count", "-1")]),
+            Err(DraftError::BadValue { field: "sign_in_count", value: "-1".into() })
+        );
+        assert!(matches!(
+            draft(&[("email", "sui@example.com"), ("username", "有 空格")]),
+            Err(DraftError::Invalid(ValidationError::Username(_)))
+        ));
+    }
+}
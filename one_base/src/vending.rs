@@ -0,0 +1,593 @@
+// ---------------- 自动售货机 ----------------
+/*
+    what: 以 Coin 为基础的售货机模拟，作为固件行为的参考模型。
+    how:
+        - 状态机: State 枚举，每个操作先 match 当前状态，非法转换返回 VendError;
+        - 审计日志: 每个事件（包括被拒绝的操作）都追加到 audit_log，序号递增，结果可复现;
+        - 找零: 在硬币库存约束下动态规划求解，耗时与金额 × 面值种数成正比，
+          凑不出则拒绝交易并保留投币; 有多种凑法时尽量多用大面值;
+        - 价格不能为负: Product::new 和 set_product 都会检查;
+        - 计数溢出: 补货、装币和投币入库都用 checked_add，超出 u32 时拒绝操作，库存保持不变;
+        - 仅收零钱模式: 强制开启，或库存无法覆盖 1..=24 美分的找零时自动开启;
+*/
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::coin::Coin;
+use crate::money::{Money, Usd};
+
+// 自动判断仅收零钱模式时，需要能找开的最大金额
+const CHANGE_PROBE_MAX: i64 = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Product {
+    pub name: String,
+    pub price: Money<Usd>,
+    pub stock: u32,
+}
+
+impl Product {
+    pub fn new(name: &str, price: Money<Usd>, stock: u32) -> Result<Self, VendError> {
+        if price.is_negative() {
+            return Err(VendError::InvalidPrice(price));
+        }
+        Ok(Product { name: name.to_string(), price, stock })
+    }
+}
+
+// 状态机: 只有这三种状态，数据随状态携带
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    Idle,
+    Collecting { inserted: Vec<Coin> },
+    Maintenance,
+}
+
+// 不带数据的状态标签，用于日志和错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    Idle,
+    Collecting,
+    Maintenance,
+}
+
+impl State {
+    pub fn kind(&self) -> StateKind {
+        match self {
+            State::Idle => StateKind::Idle,
+            State::Collecting { .. } => StateKind::Collecting,
+            State::Maintenance => StateKind::Maintenance,
+        }
+    }
+}
+
+impl fmt::Display for StateKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            StateKind::Idle => "待机",
+            StateKind::Collecting => "投币中",
+            StateKind::Maintenance => "维护中",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VendError {
+    InvalidState { action: &'static str, state: StateKind },
+    UnknownSlot(String),
+    SoldOut(String),
+    InsufficientCredit { price: Money<Usd>, credit: Money<Usd> },
+    ExactChangeRequired { price: Money<Usd>, credit: Money<Usd> },
+    CannotMakeChange(Money<Usd>),
+    InvalidPrice(Money<Usd>),
+    StockOverflow(String),
+    ReserveOverflow(Coin),
+}
+
+impl fmt::Display for VendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VendError::InvalidState { action, state } => {
+                write!(f, "当前状态「{state}」不允许操作: {action}")
+            }
+            VendError::UnknownSlot(slot) => write!(f, "货道不存在: {slot}"),
+            VendError::SoldOut(slot) => write!(f, "已售罄: {slot}"),
+            VendError::InsufficientCredit { price, credit } => {
+                write!(f, "金额不足: 价格 {price}，已投 {credit}")
+            }
+            VendError::ExactChangeRequired { price, credit } => {
+                write!(f, "仅收零钱: 价格 {price}，已投 {credit}")
+            }
+            VendError::CannotMakeChange(amount) => write!(f, "无法找零: {amount}"),
+            VendError::InvalidPrice(price) => write!(f, "价格不能为负: {price}"),
+            VendError::StockOverflow(slot) => write!(f, "货道库存超出上限: {slot}"),
+            VendError::ReserveOverflow(coin) => write!(f, "硬币库存超出上限: {coin}"),
+        }
+    }
+}
+
+impl std::error::Error for VendError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    CoinInserted(Coin),
+    Dispensed { slot: String, product: String, price: Money<Usd>, change: Vec<Coin> },
+    Refunded(Vec<Coin>),
+    Rejected(VendError),
+    ServiceOpened,
+    ServiceClosed,
+    ProductSet { slot: String, product: Product },
+    Restocked { slot: String, quantity: u32 },
+    CoinsLoaded { coin: Coin, count: u32 },
+    // 运营方强制开启/关闭
+    ExactChangeForced(bool),
+    // 指示灯实际亮灭（强制或库存不足）
+    ExactChangeMode(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub from: StateKind,
+    pub to: StateKind,
+    pub event: Event,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:04} [{} -> {}] {:?}", self.seq, self.from, self.to, self.event)
+    }
+}
+
+// 一次成功出货的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vend {
+    pub product: String,
+    pub change: Vec<Coin>,
+}
+
+#[derive(Debug)]
+pub struct VendingMachine {
+    state: State,
+    products: BTreeMap<String, Product>,
+    reserve: BTreeMap<Coin, u32>,
+    force_exact_change: bool,
+    exact_change_lit: bool,
+    audit_log: Vec<AuditEntry>,
+}
+
+impl Default for VendingMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VendingMachine {
+    // 新机器处于维护状态，配置完货道和零钱后 close_service 开始营业
+    pub fn new() -> Self {
+        VendingMachine {
+            state: State::Maintenance,
+            products: BTreeMap::new(),
+            reserve: BTreeMap::new(),
+            force_exact_change: false,
+            exact_change_lit: true,
+            audit_log: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn products(&self) -> &BTreeMap<String, Product> {
+        &self.products
+    }
+
+    pub fn reserve(&self) -> &BTreeMap<Coin, u32> {
+        &self.reserve
+    }
+
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
+    }
+
+    // 当前已投金额
+    pub fn credit(&self) -> Money<Usd> {
+        match &self.state {
+            State::Collecting { inserted } => {
+                Money::from_minor(inserted.iter().map(|c| c.value().minor()).sum())
+            }
+            _ => Money::ZERO,
+        }
+    }
+
+    pub fn exact_change_only(&self) -> bool {
+        self.exact_change_lit
+    }
+
+    // ---------- 顾客操作 ----------
+
+    pub fn insert(&mut self, coin: Coin) -> Result<Money<Usd>, VendError> {
+        let next = match &self.state {
+            State::Idle => State::Collecting { inserted: vec![coin] },
+            State::Collecting { inserted } => {
+                let mut inserted = inserted.clone();
+                inserted.push(coin);
+                State::Collecting { inserted }
+            }
+            State::Maintenance => return self.reject("insert", None),
+        };
+        self.transition(next, Event::CoinInserted(coin));
+        Ok(self.credit())
+    }
+
+    pub fn select(&mut self, slot: &str) -> Result<Vend, VendError> {
+        let inserted = match &self.state {
+            State::Collecting { inserted } => inserted.clone(),
+            _ => return self.reject("select", None),
+        };
+        let product = match self.products.get(slot) {
+            Some(p) => p.clone(),
+            None => return self.reject("select", Some(VendError::UnknownSlot(slot.to_string()))),
+        };
+        if product.stock == 0 {
+            return self.reject("select", Some(VendError::SoldOut(slot.to_string())));
+        }
+
+        let (price, credit) = (product.price, self.credit());
+        if credit < price {
+            return self.reject("select", Some(VendError::InsufficientCredit { price, credit }));
+        }
+        if self.exact_change_lit && credit != price {
+            return self.reject("select", Some(VendError::ExactChangeRequired { price, credit }));
+        }
+
+        // 投入的硬币先进入库存，再从库存中找零
+        let mut reserve = self.reserve.clone();
+        for coin in &inserted {
+            let count = reserve.entry(*coin).or_insert(0);
+            match count.checked_add(1) {
+                Some(next) => *count = next,
+                None => return self.reject("select", Some(VendError::ReserveOverflow(*coin))),
+            }
+        }
+        // 已确认 credit >= price，相减不会为负；投币金额远小于 i64 上限
+        let due = Money::from_minor(credit.minor() - price.minor());
+        let change = match make_change(due, &reserve) {
+            Some(change) => change,
+            None => return self.reject("select", Some(VendError::CannotMakeChange(due))),
+        };
+        for coin in &change {
+            if let Some(count) = reserve.get_mut(coin) {
+                *count -= 1;
+            }
+        }
+        self.reserve = reserve;
+        if let Some(p) = self.products.get_mut(slot) {
+            p.stock -= 1;
+        }
+
+        let event = Event::Dispensed {
+            slot: slot.to_string(),
+            product: product.name.clone(),
+            price,
+            change: change.clone(),
+        };
+        self.transition(State::Idle, event);
+        self.refresh_exact_change();
+        Ok(Vend { product: product.name, change })
+    }
+
+    // 退币: 原样退回投入的硬币
+    pub fn refund(&mut self) -> Result<Vec<Coin>, VendError> {
+        let inserted = match &self.state {
+            State::Collecting { inserted } => inserted.clone(),
+            _ => return self.reject("refund", None),
+        };
+        self.transition(State::Idle, Event::Refunded(inserted.clone()));
+        Ok(inserted)
+    }
+
+    // ---------- 维护操作 ----------
+
+    pub fn open_service(&mut self) -> Result<(), VendError> {
+        match self.state {
+            State::Idle => {
+                self.transition(State::Maintenance, Event::ServiceOpened);
+                Ok(())
+            }
+            _ => self.reject("open_service", None),
+        }
+    }
+
+    pub fn close_service(&mut self) -> Result<(), VendError> {
+        match self.state {
+            State::Maintenance => {
+                self.transition(State::Idle, Event::ServiceClosed);
+                self.refresh_exact_change();
+                Ok(())
+            }
+            _ => self.reject("close_service", None),
+        }
+    }
+
+    pub fn set_product(&mut self, slot: &str, product: Product) -> Result<(), VendError> {
+        self.require_maintenance("set_product")?;
+        if product.price.is_negative() {
+            return self.reject("set_product", Some(VendError::InvalidPrice(product.price)));
+        }
+        self.products.insert(slot.to_string(), product.clone());
+        self.record(Event::ProductSet { slot: slot.to_string(), product });
+        Ok(())
+    }
+
+    pub fn restock(&mut self, slot: &str, quantity: u32) -> Result<(), VendError> {
+        self.require_maintenance("restock")?;
+        let err = match self.products.get_mut(slot) {
+            Some(p) => match p.stock.checked_add(quantity) {
+                Some(stock) => {
+                    p.stock = stock;
+                    None
+                }
+                None => Some(VendError::StockOverflow(slot.to_string())),
+            },
+            None => Some(VendError::UnknownSlot(slot.to_string())),
+        };
+        if let Some(err) = err {
+            return self.reject("restock", Some(err));
+        }
+        self.record(Event::Restocked { slot: slot.to_string(), quantity });
+        Ok(())
+    }
+
+    pub fn load_coins(&mut self, coin: Coin, count: u32) -> Result<(), VendError> {
+        self.require_maintenance("load_coins")?;
+        let loaded = self.reserve.get(&coin).copied().unwrap_or(0);
+        let Some(total) = loaded.checked_add(count) else {
+            return self.reject("load_coins", Some(VendError::ReserveOverflow(coin)));
+        };
+        self.reserve.insert(coin, total);
+        self.record(Event::CoinsLoaded { coin, count });
+        Ok(())
+    }
+
+    // 运营方强制开启/关闭仅收零钱模式
+    pub fn set_exact_change_only(&mut self, on: bool) -> Result<(), VendError> {
+        self.require_maintenance("set_exact_change_only")?;
+        self.force_exact_change = on;
+        self.record(Event::ExactChangeForced(on));
+        self.refresh_exact_change();
+        Ok(())
+    }
+
+    // ---------- 内部 ----------
+
+    fn require_maintenance(&mut self, action: &'static str) -> Result<(), VendError> {
+        match self.state {
+            State::Maintenance => Ok(()),
+            _ => self.reject(action, None),
+        }
+    }
+
+    // 拒绝操作: 状态不变，但错误也要进审计日志
+    fn reject<T>(&mut self, action: &'static str, err: Option<VendError>) -> Result<T, VendError> {
+        let err = err.unwrap_or(VendError::InvalidState { action, state: self.state.kind() });
+        crate::debug!(action = action, state = self.state.kind(); "操作被拒绝: {err}");
+        self.record(Event::Rejected(err.clone()));
+        Err(err)
+    }
+
+    fn transition(&mut self, next: State, event: Event) {
+        let from = self.state.kind();
+        self.state = next;
+        self.push_log(from, event);
+    }
+
+    // 不改变状态的事件
+    fn record(&mut self, event: Event) {
+        let kind = self.state.kind();
+        self.push_log(kind, event);
+    }
+
+    fn push_log(&mut self, from: StateKind, event: Event) {
+        let entry = AuditEntry {
+            seq: self.audit_log.len() as u64 + 1,
+            from,
+            to: self.state.kind(),
+            event,
+        };
+        self.audit_log.push(entry);
+    }
+
+    fn refresh_exact_change(&mut self) {
+        let lit = self.force_exact_change
+            || (1..=CHANGE_PROBE_MAX).any(|minor| make_change(Money::from_minor(minor), &self.reserve).is_none());
+        if lit != self.exact_change_lit {
+            self.exact_change_lit = lit;
+            self.record(Event::ExactChangeMode(lit));
+        }
+    }
+}
+
+// 在库存约束下凑出 amount，优先大面值；凑不出返回 None
+// reachable[k][a]: 只用 Coin::ALL[k..] 中的硬币能否凑出 a，从小面值往大面值逐层计算;
+// 再从大面值开始，每种取尽量多的枚数，只要剩下的金额还能用更小的面值凑出
+pub fn make_change(amount: Money<Usd>, reserve: &BTreeMap<Coin, u32>) -> Option<Vec<Coin>> {
+    let amount = usize::try_from(amount.minor()).ok()?;
+    let available = |coin: &Coin| reserve.get(coin).copied().unwrap_or(0) as usize;
+    // 库存总额不够时不用建表，也限制了表的大小
+    let total: usize = Coin::ALL.iter().map(|c| available(c).saturating_mul(c.cents() as usize)).sum();
+    if amount > total {
+        return None;
+    }
+
+    let coins = Coin::ALL;
+    let mut reachable = vec![vec![false; amount + 1]; coins.len() + 1];
+    reachable[coins.len()][0] = true;
+    for k in (0..coins.len()).rev() {
+        let (cents, limit) = (coins[k].cents() as usize, available(&coins[k]));
+        // used[a]: 凑出 a 时这一层用了几枚，保证不超过库存
+        let mut used = vec![0usize; amount + 1];
+        for a in 0..=amount {
+            if reachable[k + 1][a] {
+                reachable[k][a] = true;
+            } else if a >= cents && reachable[k][a - cents] && used[a - cents] < limit {
+                reachable[k][a] = true;
+                used[a] = used[a - cents] + 1;
+            }
+        }
+    }
+    if !reachable[0][amount] {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    let mut rest = amount;
+    for (k, coin) in coins.iter().enumerate() {
+        let cents = coin.cents() as usize;
+        let n = (0..=(rest / cents).min(available(coin)))
+            .rev()
+            .find(|n| reachable[k + 1][rest - n * cents])
+            .expect("reachable[k][rest] 为真时至少有一种取法");
+        out.extend(std::iter::repeat_n(*coin, n));
+        rest -= n * cents;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cents(n: i64) -> Money<Usd> {
+        Money::from_minor(n)
+    }
+
+    fn reserve(counts: [(Coin, u32); 4]) -> BTreeMap<Coin, u32> {
+        counts.into_iter().collect()
+    }
+
+    // 课程里的售货机: 可乐 65 美分，零钱充足
+    fn machine() -> VendingMachine {
+        let mut vm = VendingMachine::new();
+        vm.set_product("A1", Product::new("可乐", cents(65), 2).unwrap()).unwrap();
+        for (coin, count) in [(Coin::Quarter, 4), (Coin::Dime, 4), (Coin::Nickel, 4), (Coin::Penny, 10)] {
+            vm.load_coins(coin, count).unwrap();
+        }
+        vm.close_service().unwrap();
+        vm
+    }
+
+    #[test]
+    fn change_prefers_large_coins_within_stock() {
+        let full = reserve([(Coin::Quarter, 10), (Coin::Dime, 10), (Coin::Nickel, 10), (Coin::Penny, 10)]);
+        assert_eq!(make_change(cents(40), &full), Some(vec![Coin::Quarter, Coin::Dime, Coin::Nickel]));
+        assert_eq!(make_change(cents(0), &full), Some(vec![]));
+
+        // 没有 5 分和 1 分: 30 只能用 3 个 10 分
+        let no_small = reserve([(Coin::Quarter, 10), (Coin::Dime, 3), (Coin::Nickel, 0), (Coin::Penny, 0)]);
+        assert_eq!(make_change(cents(30), &no_small), Some(vec![Coin::Dime; 3]));
+        assert_eq!(make_change(cents(31), &no_small), None);
+        assert_eq!(make_change(cents(-5), &no_small), None);
+    }
+
+    #[test]
+    fn large_amount_without_small_coins_finishes() {
+        let big = reserve([(Coin::Quarter, 10_000), (Coin::Dime, 10_000), (Coin::Nickel, 0), (Coin::Penny, 0)]);
+        assert_eq!(make_change(cents(300_003), &big), None);
+        assert_eq!(make_change(cents(100_000), &big).map(|c| c.len()), Some(4_000));
+    }
+
+    #[test]
+    fn vend_with_change_is_logged() {
+        let mut vm = machine();
+        assert!(!vm.exact_change_only());
+        for coin in [Coin::Quarter, Coin::Quarter, Coin::Quarter] {
+            vm.insert(coin).unwrap();
+        }
+        let vend = vm.select("A1").unwrap();
+        assert_eq!(vend.change, vec![Coin::Dime]);
+        assert_eq!(vm.state(), &State::Idle);
+        assert_eq!(vm.products()["A1"].stock, 1);
+        assert_eq!(vm.reserve()[&Coin::Quarter], 7);
+
+        let last = vm.audit_log().last().unwrap();
+        assert_eq!((last.from, last.to), (StateKind::Collecting, StateKind::Idle));
+        assert!(matches!(&last.event, Event::Dispensed { change, .. } if change == &vec![Coin::Dime]));
+        let seqs: Vec<u64> = vm.audit_log().iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, (1..=seqs.len() as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rejected_operations_keep_state_and_are_logged() {
+        let mut vm = machine();
+        assert_eq!(vm.select("A1"), Err(VendError::InvalidState { action: "select", state: StateKind::Idle }));
+        vm.insert(Coin::Dime).unwrap();
+        assert_eq!(vm.select("A1"), Err(VendError::InsufficientCredit { price: cents(65), credit: cents(10) }));
+        assert_eq!(vm.select("Z9"), Err(VendError::UnknownSlot("Z9".to_string())));
+        assert_eq!(vm.credit(), cents(10));
+        assert_eq!(vm.refund(), Ok(vec![Coin::Dime]));
+        let rejected = vm.audit_log().iter().filter(|e| matches!(e.event, Event::Rejected(_))).count();
+        assert_eq!(rejected, 3);
+    }
+
+    #[test]
+    fn exact_change_mode_is_audited() {
+        let mut vm = machine();
+        vm.open_service().unwrap();
+        vm.set_exact_change_only(true).unwrap();
+        let events: Vec<&Event> = vm.audit_log().iter().rev().take(2).map(|e| &e.event).collect();
+        assert_eq!(events, [&Event::ExactChangeMode(true), &Event::ExactChangeForced(true)]);
+        vm.close_service().unwrap();
+
+        vm.insert(Coin::Quarter).unwrap();
+        vm.insert(Coin::Quarter).unwrap();
+        vm.insert(Coin::Quarter).unwrap();
+        assert_eq!(vm.select("A1"), Err(VendError::ExactChangeRequired { price: cents(65), credit: cents(75) }));
+    }
+
+    #[test]
+    fn empty_reserve_lights_exact_change() {
+        let mut vm = VendingMachine::new();
+        vm.set_product("A1", Product::new("水", cents(50), 1).unwrap()).unwrap();
+        vm.close_service().unwrap();
+        assert!(vm.exact_change_only());
+        vm.insert(Coin::Quarter).unwrap();
+        vm.insert(Coin::Quarter).unwrap();
+        assert!(vm.select("A1").is_ok());
+    }
+
+    #[test]
+    fn negative_prices_are_rejected() {
+        assert_eq!(Product::new("退款", cents(-1), 1), Err(VendError::InvalidPrice(cents(-1))));
+        let mut vm = VendingMachine::new();
+        let product = Product { name: "退款".to_string(), price: cents(-5), stock: 1 };
+        assert_eq!(vm.set_product("A1", product), Err(VendError::InvalidPrice(cents(-5))));
+        assert!(vm.products().is_empty());
+    }
+
+    #[test]
+    fn counts_that_would_overflow_are_rejected() {
+        let mut vm = VendingMachine::new();
+        vm.set_product("A1", Product::new("水", cents(25), u32::MAX - 1).unwrap()).unwrap();
+        vm.load_coins(Coin::Quarter, u32::MAX).unwrap();
+        assert_eq!(vm.restock("A1", 2), Err(VendError::StockOverflow("A1".to_string())));
+        assert_eq!(vm.load_coins(Coin::Quarter, 1), Err(VendError::ReserveOverflow(Coin::Quarter)));
+        assert_eq!(vm.products()["A1"].stock, u32::MAX - 1);
+        assert_eq!(vm.reserve()[&Coin::Quarter], u32::MAX);
+        vm.restock("A1", 1).unwrap();
+        assert_eq!(vm.products()["A1"].stock, u32::MAX);
+        assert!(matches!(vm.audit_log().last().unwrap().event, Event::Restocked { quantity: 1, .. }));
+
+        // 投入的硬币装不进已满的库存: 拒绝交易并保留投币
+        vm.close_service().unwrap();
+        vm.insert(Coin::Quarter).unwrap();
+        assert_eq!(vm.select("A1"), Err(VendError::ReserveOverflow(Coin::Quarter)));
+        assert_eq!(vm.credit(), cents(25));
+        assert_eq!(vm.products()["A1"].stock, u32::MAX);
+        let rejected = vm.audit_log().iter().filter(|e| matches!(e.event, Event::Rejected(_))).count();
+        assert_eq!(rejected, 3);
+    }
+}
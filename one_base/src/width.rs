@@ -0,0 +1,197 @@
+// ---------------- 终端显示宽度 ----------------
+/*
+    终端中中文、全角符号、大部分 emoji 占 2 列，组合附加符号和零宽字符占 0 列。
+    {:<10} 等格式化按 char 个数补齐，遇到中文会错位，需要按显示宽度计算。
+    表格依据 Unicode East Asian Width 中的 W/F 类，只收录常用区段。
+    带颜色的文本中 ANSI 转义序列不计宽度，见 style.rs。
+    制表符跳到下一个 8 列制表位，宽度取决于它前面已经占了多少列。
+*/
+
+// 制表位间隔
+pub const TAB_WIDTH: usize = 8;
+
+// 双宽字符区段（闭区间）
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),   // 谚文字母
+    (0x231A, 0x231B),   // ⌚⌛
+    (0x2E80, 0x303E),   // CJK 部首、标点
+    (0x3041, 0x33FF),   // 假名、注音、CJK 兼容
+    (0x3400, 0x4DBF),   // CJK 扩展 A
+    (0x4E00, 0x9FFF),   // CJK 统一汉字
+    (0xA000, 0xA4CF),   // 彝文
+    (0xAC00, 0xD7A3),   // 谚文音节
+    (0xF900, 0xFAFF),   // CJK 兼容汉字
+    (0xFE30, 0xFE4F),   // CJK 兼容形式
+    (0xFF00, 0xFF60),   // 全角 ASCII
+    (0xFFE0, 0xFFE6),   // 全角符号
+    (0x1F300, 0x1F64F), // emoji
+    (0x1F900, 0x1F9FF), // emoji 补充
+    (0x20000, 0x2FFFD), // CJK 扩展 B~F
+    (0x30000, 0x3FFFD), // CJK 扩展 G
+];
+
+// 零宽字符区段（闭区间）
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F), // 组合附加符号
+    (0x200B, 0x200F), // 零宽空格、方向标记
+    (0x20D0, 0x20FF), // 符号用组合附加符号
+    (0xFE00, 0xFE0F), // 变体选择符
+    (0xFEFF, 0xFEFF), // BOM
+];
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let cp = c as u32;
+    ranges.iter().any(|&(low, high)| low <= cp && cp <= high)
+}
+
+// 单个字符的宽度; 制表符不知道所在列时按一整个制表位计算
+pub fn char_width(c: char) -> usize {
+    if c == '\t' {
+        TAB_WIDTH
+    } else if c.is_control() || in_ranges(c, ZERO) {
+        0
+    } else if in_ranges(c, WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+// ANSI 转义序列（ESC [ ... 终止字节）不占列
+pub fn str_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            width += advance(c, width);
+        }
+    }
+    width
+}
+
+// 从第 column 列开始写入 c 占用的列数
+fn advance(c: char, column: usize) -> usize {
+    if c == '\t' { TAB_WIDTH - column % TAB_WIDTH } else { char_width(c) }
+}
+
+// 把制表符按制表位展开成空格，列宽从 0 开始算
+pub fn expand_tabs(s: &str) -> String {
+    if !s.contains('\t') {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut column = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            let rest = chars.as_str();
+            skip_escape(&mut chars);
+            out.push(c);
+            out.push_str(&rest[..rest.len() - chars.as_str().len()]);
+            continue;
+        }
+        let w = advance(c, column);
+        if c == '\t' {
+            out.push_str(&" ".repeat(w));
+        } else {
+            out.push(c);
+        }
+        column += w;
+    }
+    out
+}
+
+// 跳过 ESC 之后的 CSI 序列，终止字节在 0x40..=0x7E 范围内
+pub(crate) fn skip_escape(chars: &mut std::str::Chars) {
+    if chars.clone().next() != Some('[') {
+        return;
+    }
+    chars.next();
+    for c in chars.by_ref() {
+        if ('\x40'..='\x7e').contains(&c) {
+            break;
+        }
+    }
+}
+
+// 截断到不超过 max 列，超出时以 … 结尾
+// 转义序列不计宽度并原样保留，不会从中间切开; 截断了带样式的文本时在末尾补上重置码
+pub fn truncate(s: &str, max: usize) -> String {
+    if str_width(s) <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    let mut styled = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            let rest = chars.as_str();
+            skip_escape(&mut chars);
+            out.push(c);
+            out.push_str(&rest[..rest.len() - chars.as_str().len()]);
+            styled = true;
+            continue;
+        }
+        let w = advance(c, used);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    if styled {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::{ColorMode, ERROR};
+
+    #[test]
+    fn width_of_mixed_text() {
+        assert_eq!(str_width("abc"), 3);
+        assert_eq!(str_width("你好a"), 5);
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("\x1b[1;38;5;160m错误\x1b[0m"), 4);
+    }
+
+    #[test]
+    fn truncate_plain_text() {
+        assert_eq!(truncate("solana", 10), "solana");
+        assert_eq!(truncate("solana", 4), "sol…");
+        assert_eq!(truncate("你好世界", 5), "你好…");
+        assert_eq!(truncate("solana", 0), "");
+    }
+
+    #[test]
+    fn truncate_keeps_escape_sequences_whole() {
+        let styled = ERROR.paint("错误: 余额不足").render(ColorMode::Ansi256);
+        let cut = truncate(&styled, 7);
+        assert_eq!(str_width(&cut), 7);
+        assert_eq!(crate::style::strip_ansi(&cut), "错误: …");
+        assert!(cut.starts_with("\x1b[1;38;5;160m"));
+        assert!(cut.ends_with("…\x1b[0m"));
+        // 宽度足够时原样返回
+        assert_eq!(truncate(&styled, 20), styled);
+    }
+
+    #[test]
+    fn tabs_advance_to_next_stop() {
+        assert_eq!(char_width('\t'), TAB_WIDTH);
+        assert_eq!(str_width("\t"), 8);
+        assert_eq!(str_width("ab\tc"), 9);
+        assert_eq!(str_width("你好\t"), 8);
+        assert_eq!(expand_tabs("ab\tc"), "ab      c");
+        assert_eq!(truncate("ab\tcdefgh", 8), "ab…");
+        assert_eq!(str_width(&truncate("a\tb", 9)), 9);
+    }
+}
@@ -0,0 +1,4 @@
+// ---------------- six_slices 库 ----------------
+// 切片课程的延伸，main.rs 继续保留课程示例
+
+pub mod words;
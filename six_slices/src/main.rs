@@ -9,15 +9,12 @@
     字符串slices: &str 是 &[u8] 的特殊形式，处理UTF-8;
 */
 
+use six_slices::words;
+
+// 原来将&str转为&[u8]，逐字节找 b' '，制表符、全角空格、标点都不算分隔，中文整句是一个单词;
+// 改为按 Unicode 字符类别切分，返回的切片一定落在字符边界上
 fn first_word(s: &str) -> &str {
-    // 将&str转为&[u8]，切片才能通过索引获取字符，否则panic
-    let bytes = s.as_bytes();
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return &s[0..i];
-        }
-    }
-    &s[..]
+    words::first_word(s)
 }
 
 // 求和函数
@@ -54,6 +51,10 @@ fn main() {
     let s = String::from("Sonic Sui");
     let first_word_name = first_word(&s);
     println!("{}", first_word_name);
+    println!("{}", first_word("Sonic\tSui"));  // 制表符也是分隔
+    let text = "你好，Sonic Sui!";
+    let all: Vec<&str> = words::words(text).collect();  // ["你", "好", "Sonic", "Sui"]
+    println!("{:?}", all);
 
     // cannot borrow as mutable
     // s.clear();  // 借用期间不能修改s
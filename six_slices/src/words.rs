@@ -0,0 +1,228 @@
+// ---------------- 单词切分 ----------------
+/*
+    what: 课程里的 first_word 只找 b' '，制表符、换行、全角空格（U+3000）、标点都不算分隔，
+          "你好世界" 这样的中文整句会被当成一个单词。这里按字符类别切分，返回单词切片。
+    how:
+        - Mode::Simple（默认）:
+            - Unicode 空白和标点是分隔符，其他符号（$、+、emoji）也不算单词;
+            - 字母、数字、下划线连成一个单词; ' ’ . 夹在两个单词字符之间时不切开，
+              don't、3.14 保持完整;
+            - 通用类别 M* 的附加符号（é 的 U+0301、天城文的元音符号和 virama）跟随前一个字符，不会切开单词;
+            - 汉字、平假名每个字单独成词（中文、日文不用空格分词，和 UAX #29 的默认行为一致），
+              后面跟的附加符号（异体字选择符等）归入同一个字;
+        - Mode::Uax29（features = ["uax29"]）: 交给 unicode-segmentation 按 UAX #29 单词边界切分，
+          只保留含字母或数字的片段; 需要用 words_with(s, Mode::Uax29) 显式选择，
+          feature 只是让这个模式可用，不改变 words / first_word 的结果（Cargo 会合并各 crate 的 feature）;
+        - word_ranges 返回字节范围，起止都落在字符边界上，&s[range] 不会 panic;
+          words 直接返回 &str，借用原字符串，不复制;
+*/
+
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+mod marks;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Simple,
+    #[cfg(feature = "uax29")]
+    Uax29,
+}
+
+// 第一个单词，没有单词时返回空串
+pub fn first_word(s: &str) -> &str {
+    words(s).next().unwrap_or("")
+}
+
+pub fn words(s: &str) -> Words<'_> {
+    words_with(s, Mode::default())
+}
+
+pub fn words_with(s: &str, mode: Mode) -> Words<'_> {
+    Words { source: s, ranges: word_ranges_with(s, mode) }
+}
+
+pub fn word_ranges(s: &str) -> WordRanges<'_> {
+    word_ranges_with(s, Mode::default())
+}
+
+pub fn word_ranges_with(s: &str, mode: Mode) -> WordRanges<'_> {
+    let inner = match mode {
+        Mode::Simple => Inner::Simple(s.char_indices().peekable()),
+        #[cfg(feature = "uax29")]
+        Mode::Uax29 => Inner::Uax29(unicode_segmentation::UnicodeSegmentation::unicode_word_indices(s)),
+    };
+    WordRanges { inner }
+}
+
+// ---------- 迭代器 ----------
+
+pub struct Words<'a> {
+    source: &'a str,
+    ranges: WordRanges<'a>,
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.ranges.next().map(|range| &self.source[range])
+    }
+}
+
+pub struct WordRanges<'a> {
+    inner: Inner<'a>,
+}
+
+enum Inner<'a> {
+    Simple(Peekable<CharIndices<'a>>),
+    #[cfg(feature = "uax29")]
+    Uax29(unicode_segmentation::UnicodeWordIndices<'a>),
+}
+
+impl Iterator for WordRanges<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        match &mut self.inner {
+            Inner::Simple(chars) => next_simple(chars),
+            #[cfg(feature = "uax29")]
+            Inner::Uax29(words) => words.next().map(|(start, word)| start..start + word.len()),
+        }
+    }
+}
+
+// ---------- 简单模式 ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Separator,
+    // 汉字、平假名: 每个字一个单词
+    Ideograph,
+    Word,
+    // 附加符号: 跟随前一个字符
+    Mark,
+    // 两侧都是单词字符时连接: don't、3.14
+    Mid,
+}
+
+fn class(c: char) -> Class {
+    match c {
+        '\'' | '’' | '.' => Class::Mid,
+        '_' => Class::Word,
+        c if is_ideograph(c) => Class::Ideograph,
+        c if marks::is_mark(c) => Class::Mark,
+        c if c.is_alphanumeric() => Class::Word,
+        // 空白、标点和其他符号
+        _ => Class::Separator,
+    }
+}
+
+fn next_simple(chars: &mut Peekable<CharIndices>) -> Option<Range<usize>> {
+    // 跳过分隔符和不在单词中间的 Mid; 开头孤立的附加符号也算单词
+    let (start, first) = loop {
+        let (i, c) = chars.next()?;
+        match class(c) {
+            Class::Ideograph => {
+                let mut end = i + c.len_utf8();
+                while let Some(&(j, m)) = chars.peek().filter(|&&(_, m)| class(m) == Class::Mark) {
+                    chars.next();
+                    end = j + m.len_utf8();
+                }
+                return Some(i..end);
+            }
+            Class::Word | Class::Mark => break (i, c),
+            Class::Separator | Class::Mid => {}
+        }
+    };
+    let mut end = start + first.len_utf8();
+    while let Some(&(i, c)) = chars.peek() {
+        match class(c) {
+            Class::Word | Class::Mark => {
+                chars.next();
+                end = i + c.len_utf8();
+            }
+            Class::Mid => {
+                // 需要再往后看一个字符，克隆迭代器的开销只是两个指针
+                let mut ahead = chars.clone();
+                ahead.next();
+                if !ahead.peek().is_some_and(|&(_, next)| matches!(class(next), Class::Word | Class::Mark)) {
+                    break;
+                }
+                chars.next();
+            }
+            Class::Separator | Class::Ideograph => break,
+        }
+    }
+    Some(start..end)
+}
+
+fn is_ideograph(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{309F}'        // 平假名
+        | '\u{3400}'..='\u{4DBF}'      // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}'      // CJK 统一汉字
+        | '\u{F900}'..='\u{FAFF}'      // CJK 兼容汉字
+        | '\u{20000}'..='\u{3134F}'    // CJK 扩展 B 及之后
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(s: &str) -> Vec<&str> {
+        words(s).collect()
+    }
+
+    #[test]
+    fn examples_from_main() {
+        assert_eq!(collect("你好，Sonic Sui!"), ["你", "好", "Sonic", "Sui"]);
+        assert_eq!(collect("don't stop"), ["don't", "stop"]);
+        assert_eq!(collect("pi=3.14."), ["pi", "3.14"]);
+        assert_eq!(collect("solana\u{3000}sui\tbnb\nsonic"), ["solana", "sui", "bnb", "sonic"]);
+    }
+
+    #[test]
+    fn first_word_of_empty_or_punctuation_is_empty() {
+        assert_eq!(first_word(""), "");
+        assert_eq!(first_word("  ，。!? "), "");
+        assert_eq!(first_word("'quoted' text"), "quoted");
+    }
+
+    #[test]
+    fn marks_stay_inside_words() {
+        // 分解形式的 é
+        assert_eq!(collect("cafe\u{301} au lait"), ["cafe\u{301}", "au", "lait"]);
+        // 天城文: virama U+094D、元音符号 U+0947
+        assert_eq!(collect("नमस्ते दुनिया"), ["नमस्ते", "दुनिया"]);
+        // 汉字后面的异体字选择符
+        assert_eq!(collect("葛\u{E0100}城"), ["葛\u{E0100}", "城"]);
+        // 开头孤立的附加符号
+        assert_eq!(collect(" \u{301}a"), ["\u{301}a"]);
+    }
+
+    #[test]
+    fn ranges_are_char_boundaries() {
+        let s = "a\u{301}。नमस्ते don't 你好\u{FE00}!";
+        for range in word_ranges(s) {
+            assert!(s.is_char_boundary(range.start) && s.is_char_boundary(range.end));
+            assert!(!s[range].is_empty());
+        }
+    }
+
+    #[test]
+    fn default_mode_is_simple_even_with_uax29() {
+        assert_eq!(Mode::default(), Mode::Simple);
+        assert_eq!(collect("你好 don't"), words_with("你好 don't", Mode::Simple).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "uax29")]
+    #[test]
+    fn uax29_is_opt_in() {
+        let found: Vec<&str> = words_with("你好，Sonic Sui! don't", Mode::Uax29).collect();
+        assert_eq!(found, ["你", "好", "Sonic", "Sui", "don't"]);
+    }
+}